@@ -0,0 +1,87 @@
+// bench.rs
+// Comparación de tiempos por frame: renderiza la misma animación sin y con la prueba
+// de profundidad temprana y reporta cuánto cuesta cada frame
+
+use std::time::{Duration, Instant};
+
+use crate::framebuffer::Framebuffer;
+use crate::star::Star;
+use crate::{render_frame, Meshes, RenderSettings, Scene};
+
+// Frames que se renderizan antes de medir (cachés, primeras reservas de memoria)
+const WARMUP_FRAMES: usize = 3;
+
+/// Renderiza `frames` frames dos veces desde el mismo estado inicial, sin y con prueba
+/// de profundidad temprana, e imprime el tiempo por frame de cada pasada. Con `seed` mide
+/// el sistema generado con esa semilla; sin ella, el sistema incluido
+pub fn run(
+    framebuffer: &mut Framebuffer,
+    output: &mut Framebuffer,
+    meshes: &Meshes,
+    render_settings: &RenderSettings,
+    star: Star,
+    seed: Option<u64>,
+    frames: usize,
+) {
+    let frames = frames.max(1);
+    println!(
+        "⏱️  Benchmark: {} frames a {}x{} con {} hilo(s)",
+        frames,
+        framebuffer.width,
+        framebuffer.height,
+        render_settings.threads
+    );
+
+    let original = framebuffer.early_depth_test;
+    let mut averages = Vec::with_capacity(2);
+    for early_depth_test in [false, true] {
+        framebuffer.early_depth_test = early_depth_test;
+        let times = measure(framebuffer, output, meshes, render_settings, star, seed, frames);
+
+        let total: Duration = times.iter().sum();
+        let average = total.as_secs_f64() * 1000.0 / frames as f64;
+        let min = times.iter().min().unwrap().as_secs_f64() * 1000.0;
+        let max = times.iter().max().unwrap().as_secs_f64() * 1000.0;
+        println!(
+            "  {:<18} {:>8.2} ms/frame (mín {:.2}, máx {:.2})",
+            if early_depth_test { "con early-z:" } else { "sin early-z:" },
+            average,
+            min,
+            max
+        );
+        averages.push(average);
+    }
+    framebuffer.early_depth_test = original;
+
+    println!("  Mejora: {:.2}x", averages[0] / averages[1].max(1e-9));
+}
+
+// Tiempo de cada frame de una pasada, con una escena nueva para que ambas pasadas
+// dibujen exactamente lo mismo
+fn measure(
+    framebuffer: &mut Framebuffer,
+    output: &mut Framebuffer,
+    meshes: &Meshes,
+    render_settings: &RenderSettings,
+    star: Star,
+    seed: Option<u64>,
+    frames: usize,
+) -> Vec<Duration> {
+    let mut scene = match seed {
+        Some(seed) => Scene::from_seed(seed),
+        None => Scene::new(),
+    };
+    scene.star = star;
+
+    let mut times = Vec::with_capacity(frames);
+    for frame in 0..WARMUP_FRAMES + frames {
+        let start = Instant::now();
+        framebuffer.clear();
+        scene.update();
+        render_frame(framebuffer, output, &scene, meshes, render_settings);
+        if frame >= WARMUP_FRAMES {
+            times.push(start.elapsed());
+        }
+    }
+    times
+}
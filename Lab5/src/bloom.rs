@@ -0,0 +1,234 @@
+// bloom.rs
+// Post-proceso de bloom sobre el buffer HDR: extrae los pixeles brillantes,
+// los desenfoca con un gaussiano separable en varios niveles reducidos y los suma de vuelta
+
+use crate::color::{Color, Dither, LinearColor};
+
+// Kernel gaussiano de 5 taps (coeficientes binomiales / 16); el ancho del
+// brillo sale de la cadena de niveles, no del tamaño del kernel
+const KERNEL: [f32; 5] = [1.0 / 16.0, 4.0 / 16.0, 6.0 / 16.0, 4.0 / 16.0, 1.0 / 16.0];
+
+// Imagen lineal auxiliar de un nivel de la cadena de bloom
+struct Level {
+    width: usize,
+    height: usize,
+    pixels: Vec<LinearColor>,
+}
+
+impl Level {
+    fn new(width: usize, height: usize) -> Self {
+        Level {
+            width,
+            height,
+            pixels: vec![LinearColor::black(); width * height],
+        }
+    }
+
+    // Muestreo bilineal con pesos precalculados por eje (ver `axis_weights`)
+    fn sample(&self, (x0, x1, fx): AxisWeight, (y0, y1, fy): AxisWeight) -> LinearColor {
+        let top_row = &self.pixels[y0 * self.width..(y0 + 1) * self.width];
+        let bottom_row = &self.pixels[y1 * self.width..(y1 + 1) * self.width];
+        let top = top_row[x0].lerp(top_row[x1], fx);
+        let bottom = bottom_row[x0].lerp(bottom_row[x1], fx);
+        top.lerp(bottom, fy)
+    }
+}
+
+// Índices de los dos pixeles vecinos y peso del segundo, para un eje
+type AxisWeight = (usize, usize, f32);
+
+// Para cada pixel destino, su posición en la imagen origen (`x * scale + offset`)
+// convertida a vecinos y peso bilineal; se calcula una vez por fila/columna
+fn axis_weights(target_len: usize, source_len: usize, scale: f32, offset: f32) -> Vec<AxisWeight> {
+    let max = (source_len - 1) as f32;
+    (0..target_len)
+        .map(|i| {
+            let position = (i as f32 * scale + offset).clamp(0.0, max);
+            let first = position.floor() as usize;
+            let second = (first + 1).min(source_len - 1);
+            (first, second, position - first as f32)
+        })
+        .collect()
+}
+
+pub struct Bloom {
+    pub enabled: bool,
+    // Luminancia a partir de la cual un pixel empieza a brillar
+    pub threshold: f32,
+    // Cuánto del brillo desenfocado se suma a la imagen
+    pub strength: f32,
+    // Cantidad de niveles reducidos (cada uno a la mitad del anterior)
+    pub levels: usize,
+    chain: Vec<Level>,
+    scratch: Vec<LinearColor>,
+}
+
+impl Bloom {
+    pub fn new() -> Self {
+        Bloom {
+            enabled: true,
+            threshold: 1.0,
+            strength: 0.6,
+            levels: 6,
+            chain: Vec::new(),
+            scratch: Vec::new(),
+        }
+    }
+
+    /// Suma el brillo desenfocado al buffer HDR. En los pixeles sin color HDR (fondo, texto)
+    /// el brillo se pasa por `tone_map` y se suma directo sobre su color final, cuantizado con `dither`.
+    pub fn apply(
+        &mut self,
+        hdr_buffer: &mut [Option<LinearColor>],
+        buffer: &mut [u32],
+        width: usize,
+        height: usize,
+        tone_map: impl Fn(LinearColor) -> LinearColor,
+        dither: Dither,
+    ) {
+        if !self.enabled || self.strength <= 0.0 || width < 2 || height < 2 {
+            return;
+        }
+
+        self.allocate(width, height);
+
+        // Extracción de brillo y primera reducción a la mitad
+        let threshold = self.threshold;
+        let first = &mut self.chain[0];
+        for y in 0..first.height {
+            for x in 0..first.width {
+                let mut sum = LinearColor::black();
+                for (sx, sy) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+                    let px = (x * 2 + sx).min(width - 1);
+                    let py = (y * 2 + sy).min(height - 1);
+                    if let Some(color) = hdr_buffer[py * width + px] {
+                        sum = sum + color;
+                    }
+                }
+                let color = sum * 0.25;
+                let luminance = color.luminance();
+                let bright = if luminance > threshold {
+                    color * ((luminance - threshold) / luminance)
+                } else {
+                    LinearColor::black()
+                };
+                first.pixels[y * first.width + x] = bright;
+            }
+        }
+
+        // Resto de la cadena: cada nivel es la mitad del anterior
+        for i in 1..self.chain.len() {
+            let (previous, current) = self.chain.split_at_mut(i);
+            let source = &previous[i - 1];
+            let target = &mut current[0];
+            let columns = axis_weights(target.width, source.width, 2.0, 0.5);
+            let rows = axis_weights(target.height, source.height, 2.0, 0.5);
+            for (y, &row) in rows.iter().enumerate() {
+                for (x, &column) in columns.iter().enumerate() {
+                    target.pixels[y * target.width + x] = source.sample(column, row);
+                }
+            }
+        }
+
+        for level in self.chain.iter_mut() {
+            blur(level, &mut self.scratch);
+        }
+
+        // Sube desde el nivel más chico sumando cada uno sobre el siguiente
+        for i in (1..self.chain.len()).rev() {
+            let (previous, current) = self.chain.split_at_mut(i);
+            let source = &current[0];
+            let target = &mut previous[i - 1];
+            let columns = axis_weights(target.width, source.width, 0.5, -0.25);
+            let rows = axis_weights(target.height, source.height, 0.5, -0.25);
+            for (y, &row) in rows.iter().enumerate() {
+                for (x, &column) in columns.iter().enumerate() {
+                    let index = y * target.width + x;
+                    target.pixels[index] = target.pixels[index] + source.sample(column, row);
+                }
+            }
+        }
+
+        // Composición sobre la imagen a resolución completa
+        let first = &self.chain[0];
+        let strength = self.strength;
+        let columns = axis_weights(width, first.width, 0.5, -0.25);
+        let rows = axis_weights(height, first.height, 0.5, -0.25);
+        for (y, &row) in rows.iter().enumerate() {
+            for (x, &column) in columns.iter().enumerate() {
+                let glow = first.sample(column, row) * strength;
+                if glow.luminance() < 1e-4 {
+                    continue;
+                }
+                let index = y * width + x;
+                match hdr_buffer[index] {
+                    Some(color) => hdr_buffer[index] = Some(color + glow),
+                    None => {
+                        let glow = tone_map(glow).to_srgb_dithered(dither.threshold(x, y));
+                        buffer[index] = (Color::from_hex(buffer[index]) + glow).to_hex();
+                    }
+                }
+            }
+        }
+    }
+
+    // Reserva la cadena de niveles solo si cambió el tamaño del framebuffer
+    fn allocate(&mut self, width: usize, height: usize) {
+        let first_width = width.div_ceil(2);
+        let first_height = height.div_ceil(2);
+        let levels = self.levels.max(1);
+        let matches = self.chain.len() == levels
+            && self.chain[0].width == first_width
+            && self.chain[0].height == first_height;
+        if matches {
+            return;
+        }
+
+        self.chain.clear();
+        let (mut w, mut h) = (first_width, first_height);
+        for _ in 0..levels {
+            self.chain.push(Level::new(w, h));
+            if w <= 1 || h <= 1 {
+                break;
+            }
+            w = w.div_ceil(2);
+            h = h.div_ceil(2);
+        }
+    }
+}
+
+impl Default for Bloom {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Gaussiano separable: pasada horizontal a `scratch` y vertical de vuelta al nivel
+fn blur(level: &mut Level, scratch: &mut Vec<LinearColor>) {
+    let (width, height) = (level.width, level.height);
+    scratch.clear();
+    scratch.resize(width * height, LinearColor::black());
+    let radius = KERNEL.len() as isize / 2;
+
+    for y in 0..height {
+        for x in 0..width {
+            let mut sum = LinearColor::black();
+            for (k, weight) in KERNEL.iter().enumerate() {
+                let sx = (x as isize + k as isize - radius).clamp(0, width as isize - 1) as usize;
+                sum = sum + level.pixels[y * width + sx] * *weight;
+            }
+            scratch[y * width + x] = sum;
+        }
+    }
+
+    for y in 0..height {
+        for x in 0..width {
+            let mut sum = LinearColor::black();
+            for (k, weight) in KERNEL.iter().enumerate() {
+                let sy = (y as isize + k as isize - radius).clamp(0, height as isize - 1) as usize;
+                sum = sum + scratch[sy * width + x] * *weight;
+            }
+            level.pixels[y * width + x] = sum;
+        }
+    }
+}
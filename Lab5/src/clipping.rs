@@ -0,0 +1,215 @@
+// clipping.rs
+// Recorte de triángulos contra el volumen de vista en clip space (coordenadas homogéneas),
+// antes de la división de perspectiva
+
+use nalgebra_glm::Vec4;
+
+use crate::vertex::Vertex;
+
+// Planos del volumen de vista como coeficientes (x, y, z, w): un punto está dentro si
+// `dot(plano, clip) >= 0`, es decir -w <= x <= w, -w <= y <= w y -w <= z <= w (near/far)
+const PLANES: [[f32; 4]; 6] = [
+    [1.0, 0.0, 0.0, 1.0],
+    [-1.0, 0.0, 0.0, 1.0],
+    [0.0, 1.0, 0.0, 1.0],
+    [0.0, -1.0, 0.0, 1.0],
+    [0.0, 0.0, 1.0, 1.0],
+    [0.0, 0.0, -1.0, 1.0],
+];
+
+fn distance(plane: &[f32; 4], clip: &Vec4) -> f32 {
+    plane[0] * clip.x + plane[1] * clip.y + plane[2] * clip.z + plane[3] * clip.w
+}
+
+// Un triángulo recortado por los seis planos tiene a lo sumo 3 + 6 vértices
+const MAX_VERTICES: usize = 9;
+
+/// Polígono convexo en un buffer de tamaño fijo: recortar no reserva memoria, por más
+/// triángulos que crucen los bordes al acercar la cámara
+pub struct Polygon {
+    vertices: [Vertex; MAX_VERTICES],
+    len: usize,
+}
+
+impl Polygon {
+    pub fn new() -> Self {
+        Polygon { vertices: std::array::from_fn(|_| Vertex::default()), len: 0 }
+    }
+
+    /// Vértices del polígono, en orden
+    pub fn vertices(&self) -> &[Vertex] {
+        &self.vertices[..self.len]
+    }
+
+    fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    // Un polígono convexo recortado no pasa de `MAX_VERTICES`; si el redondeo de un caso casi
+    // coplanar agregara un vértice de más, se descarta en vez de salirse del buffer
+    fn push(&mut self, vertex: Vertex) {
+        debug_assert!(self.len < MAX_VERTICES, "el polígono recortado supera {MAX_VERTICES} vértices");
+        if self.len < MAX_VERTICES {
+            self.vertices[self.len] = vertex;
+            self.len += 1;
+        }
+    }
+}
+
+impl Default for Polygon {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Resultado de recortar un triángulo contra el volumen de vista
+pub enum Clipped {
+    // Completamente dentro: se usa el triángulo original, sin copiar sus vértices
+    Inside,
+    // Completamente fuera
+    Outside,
+    // Cruza algún plano: el polígono recortado (3 o más vértices en orden) queda en el
+    // buffer que recibe `clip_triangle`
+    Polygon,
+}
+
+/// Recorta el triángulo contra los seis planos. Solo los triángulos que cruzan algún plano
+/// generan vértices nuevos, que se escriben en `polygon`; el polígono resultante se divide
+/// en un abanico de triángulos
+pub fn clip_triangle(triangle: [&Vertex; 3], polygon: &mut Polygon) -> Clipped {
+    let mut crossed = false;
+    for plane in &PLANES {
+        let inside = triangle
+            .iter()
+            .filter(|vertex| distance(plane, &vertex.clip_position) >= 0.0)
+            .count();
+        match inside {
+            0 => return Clipped::Outside,
+            3 => {}
+            _ => crossed = true,
+        }
+    }
+    if !crossed {
+        return Clipped::Inside;
+    }
+
+    // Sutherland-Hodgman: recorta el polígono contra un plano a la vez, alternando entre
+    // `polygon` y un segundo buffer. Con seis planos el resultado termina en `polygon`
+    polygon.clear();
+    for vertex in triangle {
+        polygon.push(vertex.clone());
+    }
+    let mut scratch = Polygon::new();
+    let (mut input, mut output) = (polygon, &mut scratch);
+    for plane in &PLANES {
+        clip_polygon(input, plane, output);
+        if output.len < 3 {
+            return Clipped::Outside;
+        }
+        std::mem::swap(&mut input, &mut output);
+    }
+    Clipped::Polygon
+}
+
+fn clip_polygon(polygon: &Polygon, plane: &[f32; 4], clipped: &mut Polygon) {
+    clipped.clear();
+    let vertices = polygon.vertices();
+    for (i, current) in vertices.iter().enumerate() {
+        let next = &vertices[(i + 1) % vertices.len()];
+        let current_distance = distance(plane, &current.clip_position);
+        let next_distance = distance(plane, &next.clip_position);
+
+        if current_distance >= 0.0 {
+            clipped.push(current.clone());
+        }
+        // La arista cruza el plano: agrega el punto de intersección
+        if (current_distance >= 0.0) != (next_distance >= 0.0) {
+            let t = current_distance / (current_distance - next_distance);
+            clipped.push(current.lerp(next, t));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shader::BodyParams;
+    use crate::shaders::perspective_divide;
+    use crate::Uniforms;
+
+    fn vertex(x: f32, y: f32, z: f32, w: f32) -> Vertex {
+        Vertex { clip_position: Vec4::new(x, y, z, w), ..Vertex::default() }
+    }
+
+    fn clip(triangle: [Vertex; 3], polygon: &mut Polygon) -> Clipped {
+        let [a, b, c] = &triangle;
+        clip_triangle([a, b, c], polygon)
+    }
+
+    // Todos los vértices dentro del volumen de vista, con margen para el redondeo
+    fn assert_inside_view(polygon: &Polygon) {
+        for vertex in polygon.vertices() {
+            for plane in &PLANES {
+                let d = distance(plane, &vertex.clip_position);
+                assert!(d >= -1e-5, "{:?} fuera del plano {plane:?}", vertex.clip_position);
+            }
+        }
+    }
+
+    #[test]
+    fn triangle_inside_is_kept_as_is() {
+        let mut polygon = Polygon::new();
+        let triangle = [vertex(-0.5, -0.5, 0.0, 1.0), vertex(0.5, -0.5, 0.2, 1.0), vertex(0.0, 0.9, -0.9, 1.0)];
+        assert!(matches!(clip(triangle, &mut polygon), Clipped::Inside));
+    }
+
+    #[test]
+    fn triangle_outside_one_plane_is_dropped() {
+        let mut polygon = Polygon::new();
+        let triangle = [vertex(1.5, -0.5, 0.0, 1.0), vertex(3.0, 0.5, 0.0, 1.0), vertex(2.0, 0.0, 0.5, 1.0)];
+        assert!(matches!(clip(triangle, &mut polygon), Clipped::Outside));
+    }
+
+    #[test]
+    fn vertex_behind_near_plane_is_clipped() {
+        // Un vértice con z < -w: la punta se corta y queda un cuadrilátero
+        let mut polygon = Polygon::new();
+        let triangle = [vertex(-0.5, -0.5, 0.0, 1.0), vertex(0.5, -0.5, 0.0, 1.0), vertex(0.0, 0.5, -3.0, 1.0)];
+        assert!(matches!(clip(triangle, &mut polygon), Clipped::Polygon));
+        assert_eq!(polygon.vertices().len(), 4);
+        assert_inside_view(&polygon);
+        // Los dos vértices nuevos quedan justo sobre el plano near (z = -w)
+        let on_near = polygon.vertices().iter().filter(|v| (v.clip_position.z + v.clip_position.w).abs() < 1e-5);
+        assert_eq!(on_near.count(), 2);
+    }
+
+    #[test]
+    fn triangle_crossing_w_zero_reaches_the_rasterizer_finite() {
+        // Un vértice detrás de la cámara (w < 0): sin recorte, la división de perspectiva lo
+        // mandaría al otro lado de la pantalla o daría infinitos
+        let params = BodyParams::default();
+        let uniforms = Uniforms::for_tests(&params);
+        let mut polygon = Polygon::new();
+        let triangle = [vertex(-0.5, -0.5, 0.5, 1.0), vertex(0.5, -0.5, 0.5, 1.0), vertex(0.5, 2.0, -2.0, -1.0)];
+        assert!(matches!(clip(triangle, &mut polygon), Clipped::Polygon));
+        for vertex in polygon.vertices() {
+            assert!(vertex.clip_position.w > 0.0, "w = {}", vertex.clip_position.w);
+            let mut vertex = vertex.clone();
+            perspective_divide(&mut vertex, &uniforms);
+            let p = vertex.transformed_position;
+            assert!(p.x.is_finite() && p.y.is_finite() && p.z.is_finite(), "{p:?}");
+            assert!(vertex.inv_w.is_finite() && vertex.inv_w > 0.0);
+        }
+    }
+
+    #[test]
+    fn triangle_crossing_all_six_planes_stays_in_the_buffer() {
+        // Cada vértice sale por dos planos distintos y el centro del triángulo está dentro
+        let mut polygon = Polygon::new();
+        let triangle = [vertex(-3.0, 0.0, -3.0, 1.0), vertex(3.0, -3.0, 0.0, 1.0), vertex(0.0, 3.0, 3.0, 1.0)];
+        assert!(matches!(clip(triangle, &mut polygon), Clipped::Polygon));
+        let len = polygon.vertices().len();
+        assert!((3..=MAX_VERTICES).contains(&len), "{len} vértices");
+        assert_inside_view(&polygon);
+    }
+}
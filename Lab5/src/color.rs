@@ -1,4 +1,5 @@
 use std::fmt;
+use std::sync::OnceLock;
 
 #[derive(Debug, Clone, Copy)]
 pub struct Color {
@@ -76,4 +77,525 @@ impl fmt::Display for Color {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
     write!(f, "Color(r: {}, g: {}, b: {})", self.r, self.g, self.b)
   }
-}
\ No newline at end of file
+}
+
+// ============= COLOR LINEAL EN PUNTO FLOTANTE (HDR) =============
+
+// Tabla de decodificación sRGB -> lineal para los 256 valores de un canal
+fn srgb_to_linear_table() -> &'static [f32; 256] {
+  static TABLE: OnceLock<[f32; 256]> = OnceLock::new();
+  TABLE.get_or_init(|| {
+    let mut table = [0.0; 256];
+    for (i, value) in table.iter_mut().enumerate() {
+      let c = i as f32 / 255.0;
+      *value = if c <= 0.04045 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) };
+    }
+    table
+  })
+}
+
+fn srgb_to_linear(c: f32) -> f32 {
+  let c = c.clamp(0.0, 1.0);
+  if c <= 0.04045 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) }
+}
+
+fn linear_to_srgb(c: f32) -> f32 {
+  let c = c.clamp(0.0, 1.0);
+  if c <= 0.0031308 { c * 12.92 } else { 1.055 * c.powf(1.0 / 2.4) - 0.055 }
+}
+
+// Color RGB lineal sin límite superior: los shaders pueden devolver valores > 1.0
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LinearColor {
+  pub r: f32,
+  pub g: f32,
+  pub b: f32,
+}
+
+impl LinearColor {
+  pub fn new(r: f32, g: f32, b: f32) -> Self {
+    LinearColor { r, g, b }
+  }
+
+  pub fn black() -> Self {
+    LinearColor { r: 0.0, g: 0.0, b: 0.0 }
+  }
+
+  pub fn white() -> Self {
+    LinearColor { r: 1.0, g: 1.0, b: 1.0 }
+  }
+
+  // Color escrito en sRGB de 8 bits (como en un selector de color), decodificado a lineal
+  pub fn from_srgb8(r: u8, g: u8, b: u8) -> Self {
+    LinearColor::from_srgb(Color::new(r, g, b))
+  }
+
+  // Decodifica un color sRGB de 8 bits a espacio lineal
+  pub fn from_srgb(color: Color) -> Self {
+    let table = srgb_to_linear_table();
+    LinearColor {
+      r: table[color.r as usize],
+      g: table[color.g as usize],
+      b: table[color.b as usize],
+    }
+  }
+
+  // Codifica a sRGB de 8 bits, recortando a [0, 1]
+  pub fn to_srgb(self) -> Color {
+    self.to_srgb_dithered(0.5)
+  }
+
+  // Como `to_srgb`, pero redondea hacia arriba cuando la parte fraccionaria del valor de
+  // 8 bits supera `1 - threshold` (`threshold = 0.5` es el redondeo normal)
+  pub fn to_srgb_dithered(self, threshold: f32) -> Color {
+    Color::from_float(
+      linear_to_srgb(self.r) + threshold / 255.0,
+      linear_to_srgb(self.g) + threshold / 255.0,
+      linear_to_srgb(self.b) + threshold / 255.0,
+    )
+  }
+
+  pub fn luminance(&self) -> f32 {
+    0.2126 * self.r + 0.7152 * self.g + 0.0722 * self.b
+  }
+
+  // Interpolación lineal: `t = 0` devuelve `self`, `t = 1` devuelve `other`
+  pub fn lerp(self, other: LinearColor, t: f32) -> LinearColor {
+    LinearColor {
+      r: self.r + (other.r - self.r) * t,
+      g: self.g + (other.g - self.g) * t,
+      b: self.b + (other.b - self.b) * t,
+    }
+  }
+
+  // Recorta cada canal a [min, max]
+  pub fn clamp(self, min: f32, max: f32) -> LinearColor {
+    LinearColor {
+      r: self.r.clamp(min, max),
+      g: self.g.clamp(min, max),
+      b: self.b.clamp(min, max),
+    }
+  }
+
+  fn map(self, f: impl Fn(f32) -> f32) -> LinearColor {
+    LinearColor { r: f(self.r), g: f(self.g), b: f(self.b) }
+  }
+
+  fn zip(self, other: LinearColor, f: impl Fn(f32, f32) -> f32) -> LinearColor {
+    LinearColor { r: f(self.r, other.r), g: f(self.g, other.g), b: f(self.b, other.b) }
+  }
+
+  // ----- Modos de mezcla estándar (canales recortados a [0, 1]) -----
+
+  // Oscurece: self * other
+  pub fn multiply(self, other: LinearColor) -> LinearColor {
+    self * other
+  }
+
+  // Aclara: 1 - (1 - self) * (1 - other)
+  pub fn screen(self, other: LinearColor) -> LinearColor {
+    self.clamp(0.0, 1.0).zip(other.clamp(0.0, 1.0), |a, b| 1.0 - (1.0 - a) * (1.0 - b))
+  }
+
+  // Multiply en los tonos oscuros de `self` y screen en los claros: sube el contraste
+  pub fn overlay(self, other: LinearColor) -> LinearColor {
+    self.clamp(0.0, 1.0).zip(other.clamp(0.0, 1.0), |a, b| {
+      if a < 0.5 { 2.0 * a * b } else { 1.0 - 2.0 * (1.0 - a) * (1.0 - b) }
+    })
+  }
+
+  // ----- HSV / HSL -----
+  // Se calculan sobre los valores sRGB, como en un selector de color. El tono va en grados
+  // [0, 360) y la saturación, valor y luminosidad en [0, 1].
+
+  pub fn from_hsv(hue: f32, saturation: f32, value: f32) -> LinearColor {
+    let (s, v) = (saturation.clamp(0.0, 1.0), value.clamp(0.0, 1.0));
+    let chroma = v * s;
+    hue_to_srgb(hue, chroma, v - chroma)
+  }
+
+  pub fn to_hsv(self) -> (f32, f32, f32) {
+    let (hue, max, min) = srgb_hue(self);
+    let saturation = if max > 0.0 { (max - min) / max } else { 0.0 };
+    (hue, saturation, max)
+  }
+
+  pub fn from_hsl(hue: f32, saturation: f32, lightness: f32) -> LinearColor {
+    let (s, l) = (saturation.clamp(0.0, 1.0), lightness.clamp(0.0, 1.0));
+    let chroma = (1.0 - (2.0 * l - 1.0).abs()) * s;
+    hue_to_srgb(hue, chroma, l - chroma / 2.0)
+  }
+
+  pub fn to_hsl(self) -> (f32, f32, f32) {
+    let (hue, max, min) = srgb_hue(self);
+    let lightness = (max + min) / 2.0;
+    let saturation = if max - min > 0.0 { (max - min) / (1.0 - (2.0 * lightness - 1.0).abs()) } else { 0.0 };
+    (hue, saturation, lightness)
+  }
+
+  // ----- Cuerpo negro -----
+
+  // Color de un cuerpo negro a `kelvin` grados (aproximación de Tanner Helland, válida de
+  // 1000 K a 40000 K). Solo da el tono: el canal más fuerte siempre vale 1.0.
+  // ~1900 K vela, ~3000 K enana roja, ~5800 K el Sol, ~10000 K enana blanca, >20000 K azul.
+  pub fn from_temperature(kelvin: f32) -> LinearColor {
+    let t = kelvin.clamp(1000.0, 40000.0) / 100.0;
+    let r = if t <= 66.0 { 255.0 } else { 329.69873 * (t - 60.0).powf(-0.13320476) };
+    let g = if t <= 66.0 {
+      99.4708 * t.ln() - 161.11957
+    } else {
+      288.12217 * (t - 60.0).powf(-0.07551485)
+    };
+    let b = if t >= 66.0 {
+      255.0
+    } else if t <= 19.0 {
+      0.0
+    } else {
+      138.51773 * (t - 10.0).ln() - 305.0448
+    };
+    LinearColor::new(r / 255.0, g / 255.0, b / 255.0).map(srgb_to_linear)
+  }
+}
+
+// Color con tono `hue`, croma `chroma` y desplazamiento `base` (en sRGB), decodificado a lineal
+fn hue_to_srgb(hue: f32, chroma: f32, base: f32) -> LinearColor {
+  let sector = hue.rem_euclid(360.0) / 60.0;
+  let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+  let (r, g, b) = match sector as u32 {
+    0 => (chroma, x, 0.0),
+    1 => (x, chroma, 0.0),
+    2 => (0.0, chroma, x),
+    3 => (0.0, x, chroma),
+    4 => (x, 0.0, chroma),
+    _ => (chroma, 0.0, x),
+  };
+  LinearColor::new(r + base, g + base, b + base).map(srgb_to_linear)
+}
+
+// Tono en grados y máximo/mínimo de los canales sRGB
+fn srgb_hue(color: LinearColor) -> (f32, f32, f32) {
+  let LinearColor { r, g, b } = color.map(linear_to_srgb);
+  let max = r.max(g).max(b);
+  let min = r.min(g).min(b);
+  let delta = max - min;
+  let hue = if delta <= 0.0 {
+    0.0
+  } else if max == r {
+    60.0 * ((g - b) / delta).rem_euclid(6.0)
+  } else if max == g {
+    60.0 * ((b - r) / delta + 2.0)
+  } else {
+    60.0 * ((r - g) / delta + 4.0)
+  };
+  (hue, max, min)
+}
+
+// Rampa de color con varias paradas, muestreada con un escalar. Dos paradas en la misma
+// posición producen un corte duro en vez de una transición.
+#[derive(Debug, Clone)]
+pub struct Gradient {
+  stops: Vec<(f32, LinearColor)>,
+}
+
+impl Gradient {
+  pub fn new(mut stops: Vec<(f32, LinearColor)>) -> Self {
+    assert!(!stops.is_empty(), "un gradiente necesita al menos una parada");
+    stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+    Gradient { stops }
+  }
+
+  // Color en `t`; fuera del rango de las paradas se extiende el color del extremo
+  pub fn sample(&self, t: f32) -> LinearColor {
+    let next = self.stops.partition_point(|&(position, _)| position <= t);
+    if next == 0 {
+      return self.stops[0].1;
+    }
+    if next == self.stops.len() {
+      return self.stops[next - 1].1;
+    }
+    let (start, from) = self.stops[next - 1];
+    let (end, to) = self.stops[next];
+    from.lerp(to, (t - start) / (end - start))
+  }
+}
+
+impl From<Color> for LinearColor {
+  fn from(color: Color) -> Self {
+    LinearColor::from_srgb(color)
+  }
+}
+
+impl Add for LinearColor {
+  type Output = LinearColor;
+
+  fn add(self, other: LinearColor) -> LinearColor {
+    LinearColor {
+      r: self.r + other.r,
+      g: self.g + other.g,
+      b: self.b + other.b,
+    }
+  }
+}
+
+impl Mul<f32> for LinearColor {
+  type Output = LinearColor;
+
+  fn mul(self, scalar: f32) -> LinearColor {
+    LinearColor {
+      r: self.r * scalar,
+      g: self.g * scalar,
+      b: self.b * scalar,
+    }
+  }
+}
+
+impl Mul for LinearColor {
+  type Output = LinearColor;
+
+  fn mul(self, other: LinearColor) -> LinearColor {
+    LinearColor {
+      r: self.r * other.r,
+      g: self.g * other.g,
+      b: self.b * other.b,
+    }
+  }
+}
+
+// Color lineal con canal alfa (0.0 = transparente, 1.0 = opaco)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LinearRgba {
+  pub color: LinearColor,
+  pub alpha: f32,
+}
+
+impl LinearRgba {
+  pub fn new(color: LinearColor, alpha: f32) -> Self {
+    LinearRgba { color, alpha: alpha.clamp(0.0, 1.0) }
+  }
+}
+
+// Operador de tone mapping usado al resolver el buffer HDR a 8 bits
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ToneMapping {
+  Clamp,
+  Reinhard,
+  Aces,
+}
+
+impl ToneMapping {
+  pub fn from_name(name: &str) -> Option<Self> {
+    match name.to_ascii_lowercase().as_str() {
+      "clamp" | "none" => Some(ToneMapping::Clamp),
+      "reinhard" => Some(ToneMapping::Reinhard),
+      "aces" => Some(ToneMapping::Aces),
+      _ => None,
+    }
+  }
+
+  pub fn name(&self) -> &'static str {
+    match self {
+      ToneMapping::Clamp => "clamp",
+      ToneMapping::Reinhard => "reinhard",
+      ToneMapping::Aces => "aces",
+    }
+  }
+
+  pub fn next(&self) -> Self {
+    match self {
+      ToneMapping::Clamp => ToneMapping::Reinhard,
+      ToneMapping::Reinhard => ToneMapping::Aces,
+      ToneMapping::Aces => ToneMapping::Clamp,
+    }
+  }
+
+  // Aplica exposición y la curva: el resultado es lineal en [0, 1], listo para cuantizar a sRGB
+  pub fn map(&self, color: LinearColor, exposure: f32) -> LinearColor {
+    let exposed = (color * exposure).clamp(0.0, f32::INFINITY);
+    let curve = |c: f32| -> f32 {
+      match self {
+        ToneMapping::Clamp => c,
+        ToneMapping::Reinhard => c / (1.0 + c),
+        // Aproximación de Narkowicz de la curva ACES filmic
+        ToneMapping::Aces => (c * (2.51 * c + 0.03)) / (c * (2.43 * c + 0.59) + 0.14),
+      }
+    };
+    LinearColor::new(curve(exposed.r), curve(exposed.g), curve(exposed.b))
+  }
+}
+
+// ============= DITHERING =============
+
+// Matriz de Bayer 8x8: cada valor de 0 a 63 aparece una vez, repartidos de forma que
+// cualquier subconjunto de umbrales cubre la celda de manera uniforme
+const BAYER_8X8: [[u8; 8]; 8] = [
+  [0, 32, 8, 40, 2, 34, 10, 42],
+  [48, 16, 56, 24, 50, 18, 58, 26],
+  [12, 44, 4, 36, 14, 46, 6, 38],
+  [60, 28, 52, 20, 62, 30, 54, 22],
+  [3, 35, 11, 43, 1, 33, 9, 41],
+  [51, 19, 59, 27, 49, 17, 57, 25],
+  [15, 47, 7, 39, 13, 45, 5, 37],
+  [63, 31, 55, 23, 61, 29, 53, 21],
+];
+
+// Cómo se reparte el error de cuantización al pasar de color lineal a 8 bits
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Dither {
+  // Redondeo normal: los degradados suaves muestran bandas
+  None,
+  // Dithering ordenado: el umbral de redondeo varía según la posición en una matriz de Bayer
+  Bayer,
+}
+
+impl Dither {
+  pub fn from_name(name: &str) -> Option<Self> {
+    match name.to_ascii_lowercase().as_str() {
+      "none" | "off" => Some(Dither::None),
+      "bayer" => Some(Dither::Bayer),
+      _ => None,
+    }
+  }
+
+  pub fn name(&self) -> &'static str {
+    match self {
+      Dither::None => "none",
+      Dither::Bayer => "bayer",
+    }
+  }
+
+  pub fn toggle(self) -> Self {
+    match self {
+      Dither::None => Dither::Bayer,
+      Dither::Bayer => Dither::None,
+    }
+  }
+
+  // Umbral para `LinearColor::to_srgb_dithered` en el pixel (x, y)
+  pub fn threshold(&self, x: usize, y: usize) -> f32 {
+    match self {
+      Dither::None => 0.5,
+      Dither::Bayer => (BAYER_8X8[y % 8][x % 8] as f32 + 0.5) / 64.0,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn assert_close(a: LinearColor, b: LinearColor) {
+    let close = (a.r - b.r).abs() < 1e-4 && (a.g - b.g).abs() < 1e-4 && (a.b - b.b).abs() < 1e-4;
+    assert!(close, "{a:?} != {b:?}");
+  }
+
+  // Primarios, secundarios, grises y algunos tonos intermedios
+  fn samples() -> Vec<LinearColor> {
+    let mut colors = vec![
+      LinearColor::new(1.0, 0.0, 0.0),
+      LinearColor::new(0.0, 1.0, 0.0),
+      LinearColor::new(0.0, 0.0, 1.0),
+      LinearColor::new(1.0, 1.0, 0.0),
+      LinearColor::new(0.0, 1.0, 1.0),
+      LinearColor::new(1.0, 0.0, 1.0),
+      LinearColor::from_srgb8(220, 200, 150),
+      LinearColor::from_srgb8(20, 80, 180),
+      LinearColor::from_srgb8(200, 80, 60),
+    ];
+    colors.extend([0.0, 0.05, 0.5, 1.0].map(|v| LinearColor::new(v, v, v)));
+    colors
+  }
+
+  #[test]
+  fn hsv_round_trip() {
+    for color in samples() {
+      let (h, s, v) = color.to_hsv();
+      assert_close(LinearColor::from_hsv(h, s, v), color);
+    }
+  }
+
+  #[test]
+  fn hsl_round_trip() {
+    for color in samples() {
+      let (h, s, l) = color.to_hsl();
+      assert_close(LinearColor::from_hsl(h, s, l), color);
+    }
+  }
+
+  #[test]
+  fn hue_of_primaries_and_greys() {
+    let assert_components = |actual: (f32, f32, f32), expected: (f32, f32, f32)| {
+      let close = (actual.0 - expected.0).abs() < 1e-3
+        && (actual.1 - expected.1).abs() < 1e-4
+        && (actual.2 - expected.2).abs() < 1e-4;
+      assert!(close, "{actual:?} != {expected:?}");
+    };
+    assert_components(LinearColor::new(1.0, 0.0, 0.0).to_hsv(), (0.0, 1.0, 1.0));
+    assert_components(LinearColor::new(0.0, 1.0, 0.0).to_hsv(), (120.0, 1.0, 1.0));
+    assert_components(LinearColor::new(0.0, 0.0, 1.0).to_hsl(), (240.0, 1.0, 0.5));
+    // Los grises no tienen saturación
+    assert_components(LinearColor::white().to_hsv(), (0.0, 0.0, 1.0));
+    assert_components(LinearColor::black().to_hsl(), (0.0, 0.0, 0.0));
+  }
+
+  #[test]
+  fn hue_wraps_around_360() {
+    assert_close(LinearColor::from_hsv(360.0, 1.0, 1.0), LinearColor::from_hsv(0.0, 1.0, 1.0));
+    assert_close(LinearColor::from_hsv(480.0, 0.6, 0.8), LinearColor::from_hsv(120.0, 0.6, 0.8));
+    assert_close(LinearColor::from_hsl(-120.0, 0.7, 0.4), LinearColor::from_hsl(240.0, 0.7, 0.4));
+  }
+
+  #[test]
+  fn blend_modes() {
+    let base = LinearColor::new(0.2, 0.5, 0.9);
+    // Blanco y negro son los neutros de multiply y screen
+    assert_close(base.multiply(LinearColor::white()), base);
+    assert_close(base.multiply(LinearColor::black()), LinearColor::black());
+    assert_close(base.screen(LinearColor::black()), base);
+    assert_close(base.screen(LinearColor::white()), LinearColor::white());
+    // Screen con blanco por `c` es una interpolación hacia blanco (así se aplican las nubes)
+    for c in [0.0, 0.3, 0.75] {
+      assert_close(base.screen(LinearColor::white() * c), base.lerp(LinearColor::white(), c));
+    }
+    // Overlay: gris medio es neutro; oscurece los tonos oscuros y aclara los claros
+    assert_close(base.overlay(LinearColor::new(0.5, 0.5, 0.5)), base);
+    let contrast = base.overlay(LinearColor::new(0.3, 0.3, 0.3));
+    assert!(contrast.r < base.r && contrast.b < base.b);
+    let contrast = base.overlay(LinearColor::new(0.7, 0.7, 0.7));
+    assert!(contrast.r > base.r && contrast.b > base.b);
+  }
+
+  #[test]
+  fn gradient_sampling() {
+    let red = LinearColor::new(1.0, 0.0, 0.0);
+    let green = LinearColor::new(0.0, 1.0, 0.0);
+    let blue = LinearColor::new(0.0, 0.0, 1.0);
+    // Paradas desordenadas: `new` las ordena
+    let gradient = Gradient::new(vec![(0.8, blue), (0.2, red), (0.4, green)]);
+    // En las paradas
+    assert_close(gradient.sample(0.2), red);
+    assert_close(gradient.sample(0.4), green);
+    assert_close(gradient.sample(0.8), blue);
+    // Entre paradas
+    assert_close(gradient.sample(0.3), red.lerp(green, 0.5));
+    assert_close(gradient.sample(0.7), green.lerp(blue, 0.75));
+    // Fuera de los extremos se extiende el color del extremo
+    assert_close(gradient.sample(-5.0), red);
+    assert_close(gradient.sample(0.0), red);
+    assert_close(gradient.sample(1.0), blue);
+    assert_close(gradient.sample(42.0), blue);
+  }
+
+  #[test]
+  fn gradient_hard_edge() {
+    // Dos paradas en la misma posición: corte duro, la segunda manda desde el corte
+    let (dark, light) = (LinearColor::new(0.1, 0.1, 0.1), LinearColor::new(0.9, 0.9, 0.9));
+    let gradient = Gradient::new(vec![(0.5, dark), (0.5, light)]);
+    assert_close(gradient.sample(0.499), dark);
+    assert_close(gradient.sample(0.5), light);
+    assert_close(gradient.sample(0.501), light);
+    // Con una sola parada todo es ese color
+    let single = Gradient::new(vec![(0.3, dark)]);
+    assert_close(single.sample(0.0), dark);
+    assert_close(single.sample(1.0), dark);
+  }
+}
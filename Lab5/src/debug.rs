@@ -0,0 +1,146 @@
+// debug.rs
+// Vistas de depuración del pipeline: profundidad, normales, overdraw y wireframe
+
+use nalgebra_glm::Vec3;
+
+use crate::color::{Color, LinearColor};
+use crate::framebuffer::Framebuffer;
+
+// Fragmentos por pixel que se muestran en rojo en el mapa de calor
+const OVERDRAW_MAX: u32 = 8;
+
+// Color de las aristas del wireframe
+const WIREFRAME_COLOR: u32 = 0x00FF66;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DebugView {
+    // Imagen normal, sin depuración
+    Shaded,
+    // Z-buffer en escala de grises: cerca = blanco, lejos = oscuro
+    Depth,
+    // Normal interpolada como RGB (n * 0.5 + 0.5)
+    Normals,
+    // Cantidad de fragmentos rasterizados por pixel como mapa de calor
+    Overdraw,
+}
+
+impl DebugView {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "shaded" | "none" => Some(DebugView::Shaded),
+            "depth" => Some(DebugView::Depth),
+            "normals" => Some(DebugView::Normals),
+            "overdraw" => Some(DebugView::Overdraw),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            DebugView::Shaded => "sombreado",
+            DebugView::Depth => "profundidad",
+            DebugView::Normals => "normales",
+            DebugView::Overdraw => "overdraw",
+        }
+    }
+
+    /// Activa la vista `view`, o vuelve a la imagen normal si ya estaba activa
+    pub fn toggle(self, view: DebugView) -> DebugView {
+        if self == view { DebugView::Shaded } else { view }
+    }
+}
+
+/// Reemplaza el buffer final por la vista de depuración activa y dibuja el wireframe encima
+pub fn apply(framebuffer: &mut Framebuffer) {
+    match framebuffer.debug_view {
+        DebugView::Shaded => {}
+        DebugView::Depth => draw_depth(framebuffer),
+        DebugView::Normals => draw_normals(framebuffer),
+        DebugView::Overdraw => draw_overdraw(framebuffer),
+    }
+
+    let edges = framebuffer.take_wireframe();
+    for triangle in &edges {
+        for i in 0..3 {
+            let (start, end) = (triangle[i], triangle[(i + 1) % 3]);
+            draw_edge(framebuffer, start, end);
+        }
+    }
+    framebuffer.restore_wireframe(edges);
+}
+
+fn draw_depth(framebuffer: &mut Framebuffer) {
+    // Normaliza al rango de profundidades visibles en este frame
+    let (near, far) = framebuffer
+        .zbuffer
+        .iter()
+        .filter(|depth| depth.is_finite())
+        .fold((f32::INFINITY, f32::NEG_INFINITY), |(near, far), &depth| (near.min(depth), far.max(depth)));
+    let range = (far - near).max(1e-6);
+
+    for (pixel, &depth) in framebuffer.buffer.iter_mut().zip(&framebuffer.zbuffer) {
+        *pixel = if depth.is_finite() {
+            let gray = (255.0 * (1.0 - 0.85 * (depth - near) / range)) as u8;
+            Color::new(gray, gray, gray).to_hex()
+        } else {
+            0x000000
+        };
+    }
+}
+
+fn draw_normals(framebuffer: &mut Framebuffer) {
+    let pixels = framebuffer.buffer.iter_mut().zip(&framebuffer.normal_buffer).zip(&framebuffer.zbuffer);
+    for ((pixel, normal), &depth) in pixels {
+        *pixel = if depth.is_finite() && normal.norm_squared() > 0.0 {
+            let encoded = normal.normalize() * 0.5 + Vec3::new(0.5, 0.5, 0.5);
+            Color::new((encoded.x * 255.0) as u8, (encoded.y * 255.0) as u8, (encoded.z * 255.0) as u8).to_hex()
+        } else {
+            0x000000
+        };
+    }
+}
+
+fn draw_overdraw(framebuffer: &mut Framebuffer) {
+    for (pixel, &count) in framebuffer.buffer.iter_mut().zip(&framebuffer.overdraw) {
+        *pixel = heat(count).to_hex();
+    }
+}
+
+// Mapa de calor: negro (0) y luego un barrido de tono de azul (1) a rojo (OVERDRAW_MAX o más)
+fn heat(count: u32) -> Color {
+    if count == 0 {
+        return Color::black();
+    }
+
+    let t = ((count - 1) as f32 / (OVERDRAW_MAX - 1) as f32).min(1.0);
+    LinearColor::from_hsv(240.0 * (1.0 - t), 1.0, 1.0).to_srgb()
+}
+
+// Bresenham directo sobre el buffer final, sin prueba de profundidad
+fn draw_edge(framebuffer: &mut Framebuffer, start: Vec3, end: Vec3) {
+    let (mut x, mut y) = (start.x as i32, start.y as i32);
+    let (x2, y2) = (end.x as i32, end.y as i32);
+    let dx = (x2 - x).abs();
+    let dy = -(y2 - y).abs();
+    let sx = if x < x2 { 1 } else { -1 };
+    let sy = if y < y2 { 1 } else { -1 };
+    let mut err = dx + dy;
+
+    loop {
+        if x >= 0 && y >= 0 && (x as usize) < framebuffer.width && (y as usize) < framebuffer.height {
+            framebuffer.buffer[y as usize * framebuffer.width + x as usize] = WIREFRAME_COLOR;
+        }
+        if x == x2 && y == y2 {
+            break;
+        }
+        let e2 = 2 * err;
+        if e2 >= dy {
+            err += dy;
+            x += sx;
+        }
+        if e2 <= dx {
+            err += dx;
+            y += sy;
+        }
+    }
+}
@@ -0,0 +1,201 @@
+// export.rs
+// Escritura de frames del framebuffer a disco (PPM y PNG sin dependencias externas)
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use crate::framebuffer::Framebuffer;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ImageFormat {
+    Png,
+    Ppm,
+}
+
+impl ImageFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "png" => Some(ImageFormat::Png),
+            "ppm" => Some(ImageFormat::Ppm),
+            _ => None,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ImageFormat::Png => "png",
+            ImageFormat::Ppm => "ppm",
+        }
+    }
+}
+
+/// Guarda el contenido actual del framebuffer en `path` con el formato indicado
+pub fn save_frame(framebuffer: &Framebuffer, path: &Path, format: ImageFormat) -> io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    match format {
+        ImageFormat::Png => write_png(&mut writer, framebuffer)?,
+        ImageFormat::Ppm => write_ppm(&mut writer, framebuffer)?,
+    }
+    writer.flush()
+}
+
+/// Escribe el framebuffer como PPM binario (P6)
+pub fn write_ppm<W: Write>(writer: &mut W, framebuffer: &Framebuffer) -> io::Result<()> {
+    write!(writer, "P6\n{} {}\n255\n", framebuffer.width, framebuffer.height)?;
+    writer.write_all(&rgb_bytes(&framebuffer.buffer))
+}
+
+/// Escribe el framebuffer como PNG RGB de 8 bits.
+/// Los datos van en bloques deflate sin compresión: archivos más grandes,
+/// pero idénticos byte a byte para frames idénticos.
+pub fn write_png<W: Write>(writer: &mut W, framebuffer: &Framebuffer) -> io::Result<()> {
+    writer.write_all(&[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A])?;
+
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&(framebuffer.width as u32).to_be_bytes());
+    header.extend_from_slice(&(framebuffer.height as u32).to_be_bytes());
+    header.extend_from_slice(&[8, 2, 0, 0, 0]); // 8 bits, RGB, deflate, filtro 0, sin entrelazado
+    write_png_chunk(writer, b"IHDR", &header)?;
+
+    // Cada fila empieza con el byte de filtro (0 = ninguno)
+    let row_len = framebuffer.width * 3;
+    let mut raw = Vec::with_capacity((row_len + 1) * framebuffer.height);
+    for row in framebuffer.buffer.chunks(framebuffer.width) {
+        raw.push(0);
+        raw.extend_from_slice(&rgb_bytes(row));
+    }
+    write_png_chunk(writer, b"IDAT", &zlib_stored(&raw))?;
+
+    write_png_chunk(writer, b"IEND", &[])
+}
+
+fn rgb_bytes(pixels: &[u32]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(pixels.len() * 3);
+    for &pixel in pixels {
+        bytes.push(((pixel >> 16) & 0xFF) as u8);
+        bytes.push(((pixel >> 8) & 0xFF) as u8);
+        bytes.push((pixel & 0xFF) as u8);
+    }
+    bytes
+}
+
+fn write_png_chunk<W: Write>(writer: &mut W, kind: &[u8; 4], data: &[u8]) -> io::Result<()> {
+    writer.write_all(&(data.len() as u32).to_be_bytes())?;
+    writer.write_all(kind)?;
+    writer.write_all(data)?;
+
+    let mut crc = Crc32::new();
+    crc.update(kind);
+    crc.update(data);
+    writer.write_all(&crc.finish().to_be_bytes())
+}
+
+// Stream zlib con bloques "stored" (sin compresión) de hasta 65535 bytes
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    const MAX_BLOCK: usize = 65535;
+
+    let mut out = Vec::with_capacity(data.len() + data.len() / MAX_BLOCK * 5 + 11);
+    out.extend_from_slice(&[0x78, 0x01]);
+
+    let mut blocks = data.chunks(MAX_BLOCK).peekable();
+    if blocks.peek().is_none() {
+        out.extend_from_slice(&[1, 0, 0, 0xFF, 0xFF]);
+    }
+    while let Some(block) = blocks.next() {
+        let is_last = blocks.peek().is_none();
+        let len = block.len() as u16;
+        out.push(if is_last { 1 } else { 0 });
+        out.extend_from_slice(&len.to_le_bytes());
+        out.extend_from_slice(&(!len).to_le_bytes());
+        out.extend_from_slice(block);
+    }
+
+    out.extend_from_slice(&adler32(data).to_be_bytes());
+    out
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    const MOD_ADLER: u32 = 65521;
+    let mut a: u32 = 1;
+    let mut b: u32 = 0;
+    // 5552 es el máximo de bytes antes de que `b` pueda desbordar un u32
+    for chunk in data.chunks(5552) {
+        for &byte in chunk {
+            a += byte as u32;
+            b += a;
+        }
+        a %= MOD_ADLER;
+        b %= MOD_ADLER;
+    }
+    (b << 16) | a
+}
+
+struct Crc32 {
+    table: [u32; 256],
+    value: u32,
+}
+
+impl Crc32 {
+    fn new() -> Self {
+        let mut table = [0u32; 256];
+        for (n, entry) in table.iter_mut().enumerate() {
+            let mut c = n as u32;
+            for _ in 0..8 {
+                c = if c & 1 != 0 { 0xEDB8_8320 ^ (c >> 1) } else { c >> 1 };
+            }
+            *entry = c;
+        }
+        Crc32 { table, value: 0xFFFF_FFFF }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        for &byte in data {
+            let index = ((self.value ^ byte as u32) & 0xFF) as usize;
+            self.value = self.table[index] ^ (self.value >> 8);
+        }
+    }
+
+    fn finish(&self) -> u32 {
+        self.value ^ 0xFFFF_FFFF
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn crc32(data: &[u8]) -> u32 {
+        let mut crc = Crc32::new();
+        crc.update(data);
+        crc.finish()
+    }
+
+    #[test]
+    fn crc32_known_values() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(b"The quick brown fox jumps over the lazy dog"), 0x414F_A339);
+    }
+
+    #[test]
+    fn crc32_in_pieces_matches_whole() {
+        let mut crc = Crc32::new();
+        crc.update(b"1234");
+        crc.update(b"56789");
+        assert_eq!(crc.finish(), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn adler32_known_values() {
+        assert_eq!(adler32(b""), 1);
+        assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398);
+        assert_eq!(adler32(b"123456789"), 0x091E_01DE);
+    }
+
+    #[test]
+    fn adler32_reduces_long_inputs() {
+        // Más de 5552 bytes de 0xFF: fuerza varias reducciones módulo 65521 por bloque
+        assert_eq!(adler32(&[0xFF; 100_000]), 0x149A_302C);
+    }
+}
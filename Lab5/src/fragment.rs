@@ -1,18 +1,52 @@
-use nalgebra_glm::Vec2;
-use crate::color::Color;
+use nalgebra_glm::{Vec2, Vec3};
+use crate::color::LinearColor;
 
 pub struct Fragment {
     pub position: Vec2,
-    pub color: Color,
+    pub color: LinearColor,
+    pub alpha: f32,
     pub depth: f32,
+    // Normal interpolada (para la vista de depuración de normales)
+    pub normal: Vec3,
+    // Coordenadas de textura interpoladas
+    pub tex_coords: Vec2,
 }
 
 impl Fragment {
-    pub fn new(x: f32, y: f32, color: Color, depth: f32) -> Self {
+    pub fn new(x: f32, y: f32, color: LinearColor, depth: f32) -> Self {
         Fragment {
             position: Vec2::new(x, y),
             color,
+            alpha: 1.0,
             depth,
+            normal: Vec3::zeros(),
+            tex_coords: Vec2::zeros(),
         }
     }
-}
\ No newline at end of file
+
+    pub fn with_alpha(mut self, alpha: f32) -> Self {
+        self.alpha = alpha;
+        self
+    }
+
+    pub fn with_normal(mut self, normal: Vec3) -> Self {
+        self.normal = normal;
+        self
+    }
+
+    pub fn with_tex_coords(mut self, tex_coords: Vec2) -> Self {
+        self.tex_coords = tex_coords;
+        self
+    }
+}
+
+// Atributos interpolados que el rasterizador le pasa al fragment shader
+pub struct Varyings {
+    // Posición en el espacio del modelo (sobre la esfera unitaria en los planetas)
+    pub object_position: Vec3,
+    // Posición en el espacio de mundo
+    pub world_position: Vec3,
+    // Normal transformada, normalizada
+    pub normal: Vec3,
+    pub tex_coords: Vec2,
+}
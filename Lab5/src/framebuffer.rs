@@ -1,12 +1,169 @@
 // framebuffer.rs
 
+use nalgebra_glm::Vec3;
+
+use crate::bloom::Bloom;
+use crate::color::{Color, Dither, LinearColor, ToneMapping};
+use crate::debug::{self, DebugView};
+use crate::fragment::Fragment;
+
+// Cómo se combina un fragmento con lo que ya hay en el pixel
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BlendMode {
+    // Reemplaza el pixel y escribe profundidad
+    Opaque,
+    // Mezcla por alfa: fondo * (1 - a) + color * a
+    Alpha,
+    // Suma la luz del fragmento: fondo + color * a
+    Additive,
+    // Tiñe el fondo: fondo * mix(1, color, a)
+    Multiply,
+}
+
+impl BlendMode {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "opaque" => Some(BlendMode::Opaque),
+            "alpha" => Some(BlendMode::Alpha),
+            "additive" => Some(BlendMode::Additive),
+            "multiply" => Some(BlendMode::Multiply),
+            _ => None,
+        }
+    }
+
+    fn blend(&self, base: LinearColor, color: LinearColor, alpha: f32) -> LinearColor {
+        match self {
+            BlendMode::Opaque => color,
+            BlendMode::Alpha => base.lerp(color, alpha),
+            BlendMode::Additive => base + color * alpha,
+            BlendMode::Multiply => base * LinearColor::white().lerp(color, alpha),
+        }
+    }
+}
+
+// Fragmento transparente en espera: se compone al final, de atrás hacia adelante
+struct TransparentFragment {
+    index: usize,
+    color: LinearColor,
+    alpha: f32,
+    depth: f32,
+    mode: BlendMode,
+}
+
+// Rectángulo de pixeles [x, x + width) x [y, y + height)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TileRect {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+// Destino de la rasterización: el framebuffer completo o uno de sus tiles. Recibe los
+// fragmentos uno a uno, directo desde el rasterizador
+pub trait RasterTarget {
+    // Pixeles que se pueden escribir
+    fn rect(&self) -> TileRect;
+
+    // Cuenta el fragmento en el overdraw y dice si vale la pena sombrearlo: falso si ya hay
+    // algo más cerca en el z-buffer (con la prueba temprana activa). Se llama antes del shader
+    fn depth_test(&mut self, x: usize, y: usize, depth: f32) -> bool;
+
+    // Escribe un fragmento ya sombreado con el modo de mezcla actual
+    fn write(&mut self, fragment: Fragment);
+}
+
+// Vista mutable de un tile del framebuffer: sus filas de profundidad y color HDR.
+// Cada tile es dueño de sus pixeles, así varios hilos pueden escribir a la vez.
+pub struct Tile<'a> {
+    pub rect: TileRect,
+    stride: usize,
+    zbuffer_rows: Vec<&'a mut [f32]>,
+    hdr_rows: Vec<&'a mut [Option<LinearColor>]>,
+    normal_rows: Vec<&'a mut [Vec3]>,
+    overdraw_rows: Vec<&'a mut [u32]>,
+    early_depth_test: bool,
+    blend_mode: BlendMode,
+    transparent: Vec<TransparentFragment>,
+}
+
+impl Tile<'_> {
+    // Igual que `Framebuffer::point_blend`, limitado a los pixeles del tile
+    pub fn point_blend(&mut self, x: usize, y: usize, color: LinearColor, alpha: f32, depth: f32, normal: Vec3) {
+        let rect = self.rect;
+        if x < rect.x || y < rect.y || x >= rect.x + rect.width || y >= rect.y + rect.height {
+            return;
+        }
+        let (row, column) = (y - rect.y, x - rect.x);
+        if self.zbuffer_rows[row][column] <= depth {
+            return;
+        }
+
+        if self.blend_mode == BlendMode::Opaque {
+            self.hdr_rows[row][column] = Some(color);
+            self.zbuffer_rows[row][column] = depth;
+            self.normal_rows[row][column] = normal;
+        } else if alpha > 0.0 {
+            self.transparent.push(TransparentFragment {
+                index: y * self.stride + x,
+                color,
+                alpha: alpha.min(1.0),
+                depth,
+                mode: self.blend_mode,
+            });
+        }
+    }
+}
+
+impl RasterTarget for Tile<'_> {
+    fn rect(&self) -> TileRect {
+        self.rect
+    }
+
+    fn depth_test(&mut self, x: usize, y: usize, depth: f32) -> bool {
+        let (row, column) = (y - self.rect.y, x - self.rect.x);
+        self.overdraw_rows[row][column] += 1;
+        !self.early_depth_test || self.zbuffer_rows[row][column] > depth
+    }
+
+    fn write(&mut self, fragment: Fragment) {
+        let (x, y) = (fragment.position.x as usize, fragment.position.y as usize);
+        self.point_blend(x, y, fragment.color, fragment.alpha, fragment.depth, fragment.normal);
+    }
+}
+
 pub struct Framebuffer {
     pub width: usize,
     pub height: usize,
     pub buffer: Vec<u32>,
     pub zbuffer: Vec<f32>,
+    // Color lineal de los fragmentos sombreados; `None` si el pixel ya es final (fondo, texto, líneas)
+    pub hdr_buffer: Vec<Option<LinearColor>>,
+    // Normal interpolada del fragmento opaco visible (vista de normales)
+    pub normal_buffer: Vec<Vec3>,
+    // Fragmentos rasterizados por pixel, pasen o no la prueba de profundidad (vista de overdraw)
+    pub overdraw: Vec<u32>,
+    // Capa de interfaz en 0xAARRGGBB (alfa 0 = vacío). Se compone encima de la imagen
+    // final, sin prueba de profundidad, así el HUD no se mezcla con la geometría
+    pub overlay: Vec<u32>,
+    pub bloom: Bloom,
+    pub debug_view: DebugView,
+    // Dibuja las aristas de los triángulos sobre la imagen
+    pub wireframe: bool,
+    // Dithering al cuantizar el color lineal a 8 bits (al resolver y al reescalar)
+    pub dither: Dither,
+    // Prueba de profundidad antes de sombrear; sin ella cada fragmento cubierto corre el
+    // shader y el z-buffer se revisa recién al escribir (solo para comparar tiempos)
+    pub early_depth_test: bool,
     background_color: u32,
     current_color: u32,
+    blend_mode: BlendMode,
+    transparent: Vec<TransparentFragment>,
+    wireframe_edges: Vec<[Vec3; 3]>,
+    overlay_opacity: f32,
+    overlay_dirty: bool,
+    tone_mapping: ToneMapping,
+    exposure: f32,
 }
 
 impl Framebuffer {
@@ -16,9 +173,44 @@ impl Framebuffer {
             height,
             buffer: vec![0; width * height],
             zbuffer: vec![f32::INFINITY; width * height],
+            hdr_buffer: vec![None; width * height],
+            normal_buffer: vec![Vec3::zeros(); width * height],
+            overdraw: vec![0; width * height],
+            overlay: vec![0; width * height],
+            bloom: Bloom::new(),
+            debug_view: DebugView::Shaded,
+            wireframe: false,
+            dither: Dither::None,
+            early_depth_test: true,
             background_color: 0x000000,
             current_color: 0xFFFFFF,
+            blend_mode: BlendMode::Opaque,
+            transparent: Vec::new(),
+            wireframe_edges: Vec::new(),
+            overlay_opacity: 1.0,
+            overlay_dirty: false,
+            tone_mapping: ToneMapping::Aces,
+            exposure: 1.0,
+        }
+    }
+
+    // Cambia el tamaño del framebuffer; el contenido se pierde hasta el siguiente frame
+    pub fn resize(&mut self, width: usize, height: usize) {
+        if self.width == width && self.height == height {
+            return;
         }
+
+        self.width = width;
+        self.height = height;
+        self.buffer = vec![self.background_color; width * height];
+        self.zbuffer = vec![f32::INFINITY; width * height];
+        self.hdr_buffer = vec![None; width * height];
+        self.normal_buffer = vec![Vec3::zeros(); width * height];
+        self.overdraw = vec![0; width * height];
+        self.overlay = vec![0; width * height];
+        self.overlay_dirty = false;
+        self.transparent.clear();
+        self.wireframe_edges.clear();
     }
 
     pub fn clear(&mut self) {
@@ -28,6 +220,13 @@ impl Framebuffer {
         for depth in self.zbuffer.iter_mut() {
             *depth = f32::INFINITY;
         }
+        for color in self.hdr_buffer.iter_mut() {
+            *color = None;
+        }
+        self.normal_buffer.fill(Vec3::zeros());
+        self.overdraw.fill(0);
+        self.transparent.clear();
+        self.wireframe_edges.clear();
     }
 
     pub fn point(&mut self, x: usize, y: usize, depth: f32) {
@@ -36,6 +235,285 @@ impl Framebuffer {
             if self.zbuffer[index] > depth {
                 self.buffer[index] = self.current_color;
                 self.zbuffer[index] = depth;
+                self.hdr_buffer[index] = None;
+            }
+        }
+    }
+
+    // Escribe un color lineal (sin recortar) en el buffer HDR con prueba de profundidad
+    pub fn point_hdr(&mut self, x: usize, y: usize, color: LinearColor, depth: f32) {
+        if x < self.width && y < self.height {
+            let index = y * self.width + x;
+            if self.zbuffer[index] > depth {
+                self.hdr_buffer[index] = Some(color);
+                self.zbuffer[index] = depth;
+            }
+        }
+    }
+
+    // Escribe un fragmento con el modo de mezcla actual. Los opacos van directo al buffer;
+    // los demás se guardan y se componen en `resolve`, ordenados por profundidad,
+    // así se mezclan con todo lo opaco que esté detrás sin importar el orden de dibujo.
+    pub fn point_blend(&mut self, x: usize, y: usize, color: LinearColor, alpha: f32, depth: f32, normal: Vec3) {
+        if x >= self.width || y >= self.height {
+            return;
+        }
+        let index = y * self.width + x;
+        if self.zbuffer[index] <= depth {
+            return;
+        }
+
+        if self.blend_mode == BlendMode::Opaque {
+            self.hdr_buffer[index] = Some(color);
+            self.zbuffer[index] = depth;
+            self.normal_buffer[index] = normal;
+        } else if alpha > 0.0 {
+            self.transparent.push(TransparentFragment {
+                index,
+                color,
+                alpha: alpha.min(1.0),
+                depth,
+                mode: self.blend_mode,
+            });
+        }
+    }
+
+    /// Divide el framebuffer en tiles de `tile_size` x `tile_size` (en orden de filas) y se
+    /// los pasa a `f`. Al terminar, los fragmentos transparentes de cada tile se encolan
+    /// en orden de tile, igual que si se hubieran escrito con `point_blend`.
+    pub fn with_tiles<F>(&mut self, tile_size: usize, f: F)
+    where
+        F: FnOnce(&mut [Tile]),
+    {
+        let tile_size = tile_size.max(1);
+        let columns = self.width.div_ceil(tile_size);
+        let mut tiles = Vec::with_capacity(columns * self.height.div_ceil(tile_size));
+
+        let width = self.width;
+        let rows = self
+            .zbuffer
+            .chunks_mut(width)
+            .zip(self.hdr_buffer.chunks_mut(width))
+            .zip(self.normal_buffer.chunks_mut(width))
+            .zip(self.overdraw.chunks_mut(width));
+        for (y, (((mut zbuffer_row, mut hdr_row), mut normal_row), mut overdraw_row)) in rows.enumerate() {
+            // Primera fila de una banda nueva: crea sus tiles
+            if y % tile_size == 0 {
+                for column in 0..columns {
+                    let x = column * tile_size;
+                    tiles.push(Tile {
+                        rect: TileRect {
+                            x,
+                            y,
+                            width: tile_size.min(width - x),
+                            height: tile_size.min(self.height - y),
+                        },
+                        stride: width,
+                        zbuffer_rows: Vec::with_capacity(tile_size),
+                        hdr_rows: Vec::with_capacity(tile_size),
+                        normal_rows: Vec::with_capacity(tile_size),
+                        overdraw_rows: Vec::with_capacity(tile_size),
+                        early_depth_test: self.early_depth_test,
+                        blend_mode: self.blend_mode,
+                        transparent: Vec::new(),
+                    });
+                }
+            }
+
+            // Reparte la fila entre los tiles de la banda
+            let band = &mut tiles[(y / tile_size) * columns..];
+            for tile in band.iter_mut().take(columns) {
+                let (z_part, z_rest) = zbuffer_row.split_at_mut(tile.rect.width);
+                let (hdr_part, hdr_rest) = hdr_row.split_at_mut(tile.rect.width);
+                let (normal_part, normal_rest) = normal_row.split_at_mut(tile.rect.width);
+                let (overdraw_part, overdraw_rest) = overdraw_row.split_at_mut(tile.rect.width);
+                tile.zbuffer_rows.push(z_part);
+                tile.hdr_rows.push(hdr_part);
+                tile.normal_rows.push(normal_part);
+                tile.overdraw_rows.push(overdraw_part);
+                zbuffer_row = z_rest;
+                hdr_row = hdr_rest;
+                normal_row = normal_rest;
+                overdraw_row = overdraw_rest;
+            }
+        }
+
+        f(&mut tiles);
+
+        for tile in tiles {
+            self.transparent.extend(tile.transparent);
+        }
+    }
+
+    // Compone los fragmentos transparentes de atrás hacia adelante. No escriben profundidad.
+    // El orden es estable: en un mismo pixel, a igual profundidad, gana el orden de dibujo.
+    fn composite_transparent(&mut self) {
+        let mut fragments = std::mem::take(&mut self.transparent);
+        fragments.sort_by(|a, b| b.depth.total_cmp(&a.depth));
+
+        for fragment in fragments.drain(..) {
+            if self.zbuffer[fragment.index] <= fragment.depth {
+                continue;
+            }
+            let base = match self.hdr_buffer[fragment.index] {
+                Some(color) => color,
+                None => LinearColor::from(Color::from_hex(self.buffer[fragment.index])),
+            };
+            self.hdr_buffer[fragment.index] = Some(fragment.mode.blend(base, fragment.color, fragment.alpha));
+        }
+
+        // Reutiliza la memoria en el siguiente frame
+        self.transparent = fragments;
+    }
+
+    // Compone lo transparente, aplica bloom y tone mapping y escribe el resultado en `buffer`;
+    // al final reemplaza la imagen por la vista de depuración activa
+    pub fn resolve(&mut self) {
+        self.composite_transparent();
+
+        let (tone_mapping, exposure) = (self.tone_mapping, self.exposure);
+        self.bloom.apply(
+            &mut self.hdr_buffer,
+            &mut self.buffer,
+            self.width,
+            self.height,
+            |color| tone_mapping.map(color, exposure),
+            self.dither,
+        );
+
+        for (i, (pixel, color)) in self.buffer.iter_mut().zip(&self.hdr_buffer).enumerate() {
+            if let Some(color) = color {
+                let threshold = self.dither.threshold(i % self.width, i / self.width);
+                *pixel = tone_mapping.map(*color, exposure).to_srgb_dithered(threshold).to_hex();
+            }
+        }
+
+        debug::apply(self);
+        self.composite_overlay();
+    }
+
+    // Borra la capa de interfaz (independiente de `clear`, que borra la escena)
+    pub fn clear_overlay(&mut self) {
+        if self.overlay_dirty {
+            self.overlay.fill(0);
+            self.overlay_dirty = false;
+        }
+    }
+
+    // Pinta un pixel de la capa de interfaz encima de lo que ya tenga (mezcla "over")
+    pub fn overlay_point(&mut self, x: usize, y: usize, color: u32, alpha: f32) {
+        if x >= self.width || y >= self.height || alpha <= 0.0 {
+            return;
+        }
+        let index = y * self.width + x;
+        let alpha = alpha.min(1.0);
+        let below = self.overlay[index];
+        let below_alpha = (below >> 24) as f32 / 255.0;
+
+        let out_alpha = alpha + below_alpha * (1.0 - alpha);
+        let channel = |shift: u32| {
+            let top = ((color >> shift) & 0xFF) as f32;
+            let bottom = ((below >> shift) & 0xFF) as f32;
+            ((top * alpha + bottom * below_alpha * (1.0 - alpha)) / out_alpha).round() as u32
+        };
+        let alpha_byte = (out_alpha * 255.0).round() as u32;
+        self.overlay[index] = (alpha_byte << 24) | (channel(16) << 16) | (channel(8) << 8) | channel(0);
+        self.overlay_dirty = true;
+    }
+
+    // Compone la capa de interfaz sobre `buffer`, mezclando en espacio lineal
+    pub fn composite_overlay(&mut self) {
+        if !self.overlay_dirty || self.overlay_opacity <= 0.0 {
+            return;
+        }
+        for (pixel, &overlay) in self.buffer.iter_mut().zip(&self.overlay) {
+            let alpha = (overlay >> 24) as f32 / 255.0 * self.overlay_opacity;
+            if alpha <= 0.0 {
+                continue;
+            }
+            let scene = LinearColor::from(Color::from_hex(*pixel));
+            let ui = LinearColor::from(Color::from_hex(overlay & 0xFFFFFF));
+            *pixel = scene.lerp(ui, alpha).to_srgb().to_hex();
+        }
+    }
+
+    // Guarda un triángulo en pantalla para dibujar sus aristas al resolver
+    pub fn queue_wireframe(&mut self, triangle: [Vec3; 3]) {
+        self.wireframe_edges.push(triangle);
+    }
+
+    pub fn take_wireframe(&mut self) -> Vec<[Vec3; 3]> {
+        std::mem::take(&mut self.wireframe_edges)
+    }
+
+    // Devuelve la memoria de `take_wireframe` para reutilizarla en el siguiente frame
+    pub fn restore_wireframe(&mut self, mut edges: Vec<[Vec3; 3]>) {
+        edges.clear();
+        self.wireframe_edges = edges;
+    }
+
+    /// Escala este framebuffer al tamaño de `target` en espacio lineal: al reducir promedia
+    /// los pixeles que cubre cada pixel destino (supersampling, escala > 100%) y al
+    /// ampliar interpola bilinealmente (escala < 100%)
+    pub fn resample(&self, target: &mut Framebuffer) {
+        if self.width == target.width && self.height == target.height {
+            target.buffer.copy_from_slice(&self.buffer);
+            return;
+        }
+
+        let decode = |x: usize, y: usize| LinearColor::from(Color::from_hex(self.buffer[y * self.width + x]));
+        let scale_x = self.width as f32 / target.width as f32;
+        let scale_y = self.height as f32 / target.height as f32;
+
+        if scale_x >= 1.0 && scale_y >= 1.0 {
+            // Para cada pixel destino, el rango de pixeles origen que lo cubren
+            let spans = |target_len: usize, source_len: usize, scale: f32| -> Vec<(usize, usize)> {
+                (0..target_len)
+                    .map(|i| {
+                        let start = ((i as f32 * scale) as usize).min(source_len - 1);
+                        let end = (((i + 1) as f32 * scale).ceil() as usize).clamp(start + 1, source_len);
+                        (start, end)
+                    })
+                    .collect()
+            };
+            let columns = spans(target.width, self.width, scale_x);
+            let rows = spans(target.height, self.height, scale_y);
+
+            for (y, &(y0, y1)) in rows.iter().enumerate() {
+                for (x, &(x0, x1)) in columns.iter().enumerate() {
+                    let mut sum = LinearColor::black();
+                    for sy in y0..y1 {
+                        for sx in x0..x1 {
+                            sum = sum + decode(sx, sy);
+                        }
+                    }
+                    let weight = 1.0 / ((y1 - y0) * (x1 - x0)) as f32;
+                    let threshold = self.dither.threshold(x, y);
+                    target.buffer[y * target.width + x] = (sum * weight).to_srgb_dithered(threshold).to_hex();
+                }
+            }
+        } else {
+            // Vecinos y peso bilineal por eje, muestreando en el centro de cada pixel
+            let weights = |target_len: usize, source_len: usize, scale: f32| -> Vec<(usize, usize, f32)> {
+                let max = (source_len - 1) as f32;
+                (0..target_len)
+                    .map(|i| {
+                        let position = ((i as f32 + 0.5) * scale - 0.5).clamp(0.0, max);
+                        let first = position as usize;
+                        (first, (first + 1).min(source_len - 1), position - first as f32)
+                    })
+                    .collect()
+            };
+            let columns = weights(target.width, self.width, scale_x);
+            let rows = weights(target.height, self.height, scale_y);
+
+            for (y, &(y0, y1, fy)) in rows.iter().enumerate() {
+                for (x, &(x0, x1, fx)) in columns.iter().enumerate() {
+                    let top = decode(x0, y0).lerp(decode(x1, y0), fx);
+                    let bottom = decode(x0, y1).lerp(decode(x1, y1), fx);
+                    let threshold = self.dither.threshold(x, y);
+                    target.buffer[y * target.width + x] = top.lerp(bottom, fy).to_srgb_dithered(threshold).to_hex();
+                }
             }
         }
     }
@@ -47,4 +525,46 @@ impl Framebuffer {
     pub fn set_current_color(&mut self, color: u32) {
         self.current_color = color;
     }
-}
\ No newline at end of file
+
+    // Opacidad de toda la capa de interfaz (0 = oculta, 1 = opaca)
+    pub fn set_overlay_opacity(&mut self, opacity: f32) {
+        self.overlay_opacity = opacity.clamp(0.0, 1.0);
+    }
+
+    pub fn set_blend_mode(&mut self, mode: BlendMode) {
+        self.blend_mode = mode;
+    }
+
+    pub fn set_tone_mapping(&mut self, tone_mapping: ToneMapping) {
+        self.tone_mapping = tone_mapping;
+    }
+
+    pub fn tone_mapping(&self) -> ToneMapping {
+        self.tone_mapping
+    }
+
+    pub fn set_exposure(&mut self, exposure: f32) {
+        self.exposure = exposure.max(0.0);
+    }
+
+    pub fn exposure(&self) -> f32 {
+        self.exposure
+    }
+}
+
+impl RasterTarget for Framebuffer {
+    fn rect(&self) -> TileRect {
+        TileRect { x: 0, y: 0, width: self.width, height: self.height }
+    }
+
+    fn depth_test(&mut self, x: usize, y: usize, depth: f32) -> bool {
+        let index = y * self.width + x;
+        self.overdraw[index] += 1;
+        !self.early_depth_test || self.zbuffer[index] > depth
+    }
+
+    fn write(&mut self, fragment: Fragment) {
+        let (x, y) = (fragment.position.x as usize, fragment.position.y as usize);
+        self.point_blend(x, y, fragment.color, fragment.alpha, fragment.depth, fragment.normal);
+    }
+}
@@ -0,0 +1,380 @@
+// generator.rs
+// Generador procedural de sistemas: a partir de una semilla elige cuántos planetas hay y,
+// para cada uno, su shader, paleta, tamaño, órbita, rotación, lunas y anillos.
+// Todo sale de la semilla, así que la misma semilla reproduce el mismo sistema
+
+use std::f32::consts::TAU;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use nalgebra_glm::Vec3;
+
+use crate::color::{Gradient, LinearColor};
+use crate::params::{GasGiantParams, IceParams, MoonParams, RingParams, RockyParams, VolcanicParams};
+use crate::shader::{
+    BodyParams, FragmentShader, GasGiantShader, IcePlanetShader, MoonShader, RingShader, RockyPlanetShader,
+    VolcanicPlanetShader,
+};
+use crate::{CelestialBody, Moon, Rings};
+
+// Radio de la primera órbita; las siguientes se separan según el tamaño de cada planeta
+const FIRST_ORBIT: f32 = 230.0;
+
+// Espacio libre entre el borde de un planeta (con anillos y lunas) y el siguiente
+const ORBIT_GAP: (f32, f32) = (45.0, 100.0);
+
+/// Generador pseudoaleatorio SplitMix64: pequeño, sin dependencias y con la misma
+/// secuencia en cualquier plataforma
+struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    // Semilla del ruido de un cuerpo: los 32 bits altos
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    // Número en [0, 1)
+    fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    fn range(&mut self, min: f32, max: f32) -> f32 {
+        min + (max - min) * self.next_f32()
+    }
+
+    // Entero en [min, max], ambos incluidos
+    fn range_usize(&mut self, min: usize, max: usize) -> usize {
+        min + (self.next_u64() % (max - min + 1) as u64) as usize
+    }
+
+    fn chance(&mut self, probability: f32) -> bool {
+        self.next_f32() < probability
+    }
+}
+
+/// Semilla nueva para la tecla P: distinta en cada llamada, pero se imprime para poder
+/// repetir el sistema con `--seed`
+pub fn random_seed() -> u64 {
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_nanos() as u64);
+    Rng::new(nanos).next_u64()
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Kind {
+    Rocky,
+    Volcanic,
+    GasGiant,
+    Ice,
+}
+
+/// Genera los planetas de un sistema, del más cercano al sol al más lejano
+pub fn generate_bodies(seed: u64) -> Vec<CelestialBody> {
+    let mut rng = Rng::new(seed);
+    let count = rng.range_usize(3, 5);
+
+    let mut bodies: Vec<CelestialBody> = Vec::with_capacity(count);
+    for i in 0..count {
+        // 0 = órbita interior, 1 = la más lejana
+        let distance = i as f32 / (count - 1) as f32;
+        let kind = pick_kind(&mut rng, distance);
+        let mut body = generate_body(&mut rng, kind);
+
+        body.orbital_radius = match bodies.last() {
+            Some(prev) => prev.orbital_radius + prev.visible_radius + body.visible_radius + rng.range(ORBIT_GAP.0, ORBIT_GAP.1),
+            None => FIRST_ORBIT + body.visible_radius,
+        };
+        // Como en un sistema real, los planetas lejanos orbitan más despacio (tercera ley de Kepler)
+        body.orbital_speed = 0.02 * (250.0 / body.orbital_radius).powf(1.5) * rng.range(0.8, 1.2);
+        bodies.push(body);
+    }
+    bodies
+}
+
+// Los planetas rocosos y volcánicos quedan cerca de la estrella; los gigantes y los
+// helados, lejos
+fn pick_kind(rng: &mut Rng, distance: f32) -> Kind {
+    let roll = rng.next_f32();
+    if distance < 0.34 {
+        if roll < 0.65 { Kind::Rocky } else { Kind::Volcanic }
+    } else if distance < 0.67 {
+        if roll < 0.3 { Kind::Rocky } else if roll < 0.8 { Kind::GasGiant } else { Kind::Ice }
+    } else if roll < 0.5 {
+        Kind::GasGiant
+    } else {
+        Kind::Ice
+    }
+}
+
+// Cuerpo completo salvo la órbita, que depende de los planetas anteriores
+fn generate_body(rng: &mut Rng, kind: Kind) -> CelestialBody {
+    let (scale, shader, name): (f32, Box<dyn FragmentShader>, &'static str) = match kind {
+        Kind::Rocky => (rng.range(35.0, 60.0), Box::new(RockyPlanetShader { params: rocky_params(rng) }), "Planeta Rocoso"),
+        Kind::Volcanic => (
+            rng.range(30.0, 55.0),
+            Box::new(VolcanicPlanetShader { params: volcanic_params(rng) }),
+            "Planeta Volcánico",
+        ),
+        Kind::GasGiant => (
+            rng.range(70.0, 100.0),
+            Box::new(GasGiantShader { params: gas_giant_params(rng) }),
+            "Gigante Gaseoso",
+        ),
+        Kind::Ice => (rng.range(50.0, 75.0), Box::new(IcePlanetShader { params: ice_params(rng) }), "Planeta Helado"),
+    };
+
+    // Luz ambiental y halo de atmósfera: los volcánicos emiten su propia luz y los helados
+    // reflejan más; solo los rocosos y helados tienen atmósfera
+    let params = match kind {
+        Kind::Rocky => BodyParams::new(0.2, rng.range(0.0, 0.7)),
+        Kind::Volcanic => BodyParams::new(0.4, 0.0),
+        Kind::GasGiant => BodyParams::new(0.2, 0.0),
+        Kind::Ice => BodyParams::new(0.3, rng.range(0.2, 0.5)),
+    };
+
+    // La esfera del modelo tiene radio 1: el radio en pantalla es la escala
+    let mut extent = scale;
+
+    let ring_chance = match kind {
+        Kind::GasGiant => 0.6,
+        Kind::Ice => 0.4,
+        Kind::Rocky | Kind::Volcanic => 0.05,
+    };
+    let rings = rng.chance(ring_chance).then(|| {
+        let inner_radius = rng.range(1.3, 1.7);
+        let outer_radius = inner_radius + rng.range(0.4, 0.9);
+        extent = scale * outer_radius;
+        Rings::new(inner_radius, outer_radius, rng.range(0.3, 0.6), RingShader { params: ring_params(rng) })
+    });
+
+    let max_moons = match kind {
+        Kind::GasGiant => 3,
+        Kind::Rocky | Kind::Ice => 2,
+        Kind::Volcanic => 1,
+    };
+    let moon_count = rng.range_usize(0, max_moons);
+    let mut moons = Vec::with_capacity(moon_count);
+    for _ in 0..moon_count {
+        let moon_scale = scale * rng.range(0.2, 0.35);
+        // Cada luna orbita por fuera del planeta, sus anillos y las lunas anteriores
+        let orbital_radius = extent + moon_scale + rng.range(10.0, 25.0);
+        extent = orbital_radius + moon_scale;
+        moons.push(Moon {
+            orbital_radius,
+            orbital_angle: rng.range(0.0, TAU),
+            // Las lunas cercanas giran más rápido
+            orbital_speed: 0.05 * (100.0 / orbital_radius).powf(1.5) * rng.range(0.8, 1.2),
+            scale: moon_scale,
+            shader: MoonShader { params: moon_params(rng) },
+            params: BodyParams::new(0.15, 0.0),
+        });
+    }
+
+    CelestialBody {
+        orbital_radius: 0.0,
+        orbital_angle: rng.range(0.0, TAU),
+        orbital_speed: 0.0,
+        scale,
+        visible_radius: extent,
+        rotation: Vec3::new(0.0, 0.0, 0.0),
+        shader,
+        params,
+        rotation_speed: rng.range(0.005, 0.025),
+        name,
+        moons,
+        rings,
+    }
+}
+
+// ----- Paletas y parámetros de cada tipo -----
+// Los tonos se eligen en HSV (grados) alrededor de los del preajuste de cada shader
+
+fn rocky_params(rng: &mut Rng) -> RockyParams {
+    let land_hue = rng.range(15.0, 140.0);
+    let ocean_hue = rng.range(170.0, 250.0);
+    // Algunos planetas son desiertos sin océanos, como Marte
+    let sea_level = if rng.chance(0.3) { 0.0 } else { rng.range(0.4, 0.6) };
+    RockyParams {
+        land: Gradient::new(vec![
+            (0.4, LinearColor::from_hsv(land_hue + 20.0, 0.35, 0.85)),
+            (0.5, LinearColor::from_hsv(land_hue, 0.6, 0.55)),
+            (0.62, LinearColor::from_hsv(land_hue - 10.0, 0.65, 0.4)),
+            (0.72, LinearColor::from_hsv(land_hue, 0.1, 0.5)),
+        ]),
+        ocean: Gradient::new(vec![
+            (0.4, LinearColor::from_hsv(ocean_hue, 0.85, 0.7)),
+            (0.48, LinearColor::from_hsv(ocean_hue - 10.0, 0.7, 0.8)),
+        ]),
+        ice: LinearColor::from_srgb8(240, 250, 255),
+        noise_scale: rng.range(2.5, 4.5),
+        detail_scale: rng.range(6.0, 12.0),
+        octaves: 5,
+        sea_level,
+        cloud_coverage: rng.range(0.0, 0.5),
+        ice_cap_latitude: rng.range(0.0, 0.2),
+        spin: rng.range(0.05, 0.15),
+        seed: rng.next_u32(),
+    }
+}
+
+fn volcanic_params(rng: &mut Rng) -> VolcanicParams {
+    let rock_hue = rng.range(0.0, 40.0);
+    let lava_hue = rng.range(5.0, 35.0);
+    VolcanicParams {
+        dark_rock: LinearColor::from_hsv(rock_hue, 0.25, 0.16),
+        light_rock: LinearColor::from_hsv(rock_hue, 0.15, 0.32),
+        lava_cool: LinearColor::from_hsv(lava_hue, 0.9, 1.0),
+        lava_hot: LinearColor::from_hsv(lava_hue + 20.0, 0.8, 1.0),
+        lava_scale: rng.range(4.0, 7.0),
+        rock_scale: rng.range(8.0, 12.0),
+        octaves: 4,
+        lava_threshold: rng.range(0.55, 0.7),
+        lava_intensity: rng.range(1.2, 2.0),
+        seed: rng.next_u32(),
+    }
+}
+
+fn gas_giant_params(rng: &mut Rng) -> GasGiantParams {
+    let hue = rng.range(0.0, 360.0);
+    let saturation = rng.range(0.2, 0.5);
+    let dark = LinearColor::from_hsv(hue, saturation, 0.7);
+    let mid = LinearColor::from_hsv(hue + 8.0, saturation * 0.9, 0.78);
+    let light = LinearColor::from_hsv(hue + 15.0, saturation * 0.5, 0.86);
+    // La tormenta contrasta con las bandas; a veces no hay
+    let storm_radius = if rng.chance(0.6) { rng.range(0.2, 0.45) } else { 0.0 };
+    GasGiantParams {
+        bands: Gradient::new(vec![(0.0, dark), (0.45, mid), (0.55, mid), (1.0, light)]),
+        storm_color: LinearColor::from_hsv(hue + rng.range(-40.0, 40.0), 0.7, 0.78),
+        band_frequency: rng.range(5.0, 12.0),
+        turbulence_scale: rng.range(8.0, 14.0),
+        octaves: 4,
+        storm_center: (rng.range(-0.5, 0.5), rng.range(-0.5, 0.5)),
+        storm_radius,
+        spin: rng.range(0.03, 0.08),
+        seed: rng.next_u32(),
+    }
+}
+
+fn ice_params(rng: &mut Rng) -> IceParams {
+    let hue = rng.range(170.0, 230.0);
+    let deep = LinearColor::from_hsv(hue, 0.55, 0.86);
+    let ice = LinearColor::from_hsv(hue, 0.3, 1.0);
+    let white = LinearColor::from_hsv(hue, 0.1, 1.0);
+    IceParams {
+        palette: Gradient::new(vec![(0.4, deep), (0.4, ice), (0.6, ice), (0.6, white)]),
+        noise_scale: rng.range(6.0, 10.0),
+        octaves: 4,
+        crack_scale: rng.range(15.0, 25.0),
+        crack_threshold: rng.range(0.6, 0.8),
+        spin: rng.range(0.1, 0.2),
+        seed: rng.next_u32(),
+    }
+}
+
+fn moon_params(rng: &mut Rng) -> MoonParams {
+    // Grises con un tinte leve
+    let hue = rng.range(0.0, 360.0);
+    let tint = rng.range(0.0, 0.15);
+    MoonParams {
+        palette: Gradient::new(vec![
+            (0.4, LinearColor::from_hsv(hue, tint, 0.47)),
+            (0.4, LinearColor::from_hsv(hue, tint, 0.78)),
+            (0.7, LinearColor::from_hsv(hue, tint, 0.78)),
+            (0.7, LinearColor::from_hsv(hue, tint, 0.31)),
+        ]),
+        crater_scale: rng.range(10.0, 20.0),
+        octaves: 4,
+        seed: rng.next_u32(),
+    }
+}
+
+fn ring_params(rng: &mut Rng) -> RingParams {
+    let hue = rng.range(0.0, 60.0);
+    let saturation = rng.range(0.1, 0.35);
+    RingParams {
+        light: LinearColor::from_hsv(hue, saturation, 0.78),
+        dark: LinearColor::from_hsv(hue, saturation, 0.47),
+        band_frequency: rng.range(14.0, 28.0),
+        gap_threshold: rng.range(0.7, 0.9),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Resumen comparable de un sistema: los shaders de los planetas son `dyn`, así que se
+    // compara todo lo demás (el tipo queda en el nombre)
+    fn describe(bodies: &[CelestialBody]) -> Vec<String> {
+        bodies
+            .iter()
+            .map(|body| {
+                let moons: Vec<String> = body
+                    .moons
+                    .iter()
+                    .map(|moon| {
+                        format!(
+                            "{} {} {} {} {:?}",
+                            moon.orbital_radius, moon.orbital_angle, moon.orbital_speed, moon.scale, moon.shader.params
+                        )
+                    })
+                    .collect();
+                let rings = body.rings.as_ref().map(|rings| format!("{} {} {:?}", rings.tilt, rings.mesh.len(), rings.shader.params));
+                format!(
+                    "{} {} {} {} {} {} {} {:?} {:?} {:?}",
+                    body.name,
+                    body.orbital_radius,
+                    body.orbital_angle,
+                    body.orbital_speed,
+                    body.scale,
+                    body.visible_radius,
+                    body.rotation_speed,
+                    body.params,
+                    moons,
+                    rings
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn same_seed_gives_the_same_system() {
+        for seed in [0, 7, 42, u64::MAX] {
+            assert_eq!(describe(&generate_bodies(seed)), describe(&generate_bodies(seed)), "semilla {seed}");
+        }
+    }
+
+    #[test]
+    fn different_seeds_give_different_systems() {
+        let systems: Vec<Vec<String>> = (0..20).map(|seed| describe(&generate_bodies(seed))).collect();
+        for a in 0..systems.len() {
+            for b in a + 1..systems.len() {
+                assert_ne!(systems[a], systems[b], "semillas {a} y {b}");
+            }
+        }
+    }
+
+    #[test]
+    fn generated_orbits_do_not_overlap() {
+        for seed in 0..50 {
+            let bodies = generate_bodies(seed);
+            assert!((3..=5).contains(&bodies.len()), "semilla {seed}: {} planetas", bodies.len());
+            for pair in bodies.windows(2) {
+                let gap = pair[1].orbital_radius - pair[0].orbital_radius;
+                assert!(gap >= pair[0].visible_radius + pair[1].visible_radius, "semilla {seed}");
+            }
+        }
+    }
+}
@@ -0,0 +1,335 @@
+// gif.rs
+// Codificador de GIF animado: cuantización de paleta por median cut y compresión LZW
+
+use std::io::{self, Write};
+
+const MAX_COLORS: usize = 256;
+const MIN_CODE_SIZE: u8 = 8;
+const CLEAR_CODE: u16 = 1 << MIN_CODE_SIZE;
+const END_CODE: u16 = CLEAR_CODE + 1;
+const MAX_CODE: u16 = 4095;
+
+/// Escribe un GIF animado frame por frame sobre cualquier `Write`
+pub struct GifEncoder<W: Write> {
+    writer: W,
+    width: usize,
+    height: usize,
+}
+
+impl<W: Write> GifEncoder<W> {
+    /// Escribe la cabecera del GIF (tamaño lógico y bucle infinito)
+    pub fn new(mut writer: W, width: usize, height: usize) -> io::Result<Self> {
+        writer.write_all(b"GIF89a")?;
+        writer.write_all(&(width as u16).to_le_bytes())?;
+        writer.write_all(&(height as u16).to_le_bytes())?;
+        writer.write_all(&[0x00, 0x00, 0x00])?; // sin paleta global
+
+        // Extensión NETSCAPE2.0: repetir para siempre
+        writer.write_all(&[0x21, 0xFF, 0x0B])?;
+        writer.write_all(b"NETSCAPE2.0")?;
+        writer.write_all(&[0x03, 0x01, 0x00, 0x00, 0x00])?;
+
+        Ok(GifEncoder { writer, width, height })
+    }
+
+    /// Agrega un frame con su propia paleta local. `delay_cs` está en centésimas de segundo.
+    pub fn add_frame(&mut self, pixels: &[u32], delay_cs: u16) -> io::Result<()> {
+        if pixels.len() != self.width * self.height {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "el tamaño del frame no coincide con el del GIF",
+            ));
+        }
+
+        let (palette, indices) = quantize(pixels);
+
+        // Graphic Control Extension: solo el retardo, sin transparencia
+        self.writer.write_all(&[0x21, 0xF9, 0x04, 0x00])?;
+        self.writer.write_all(&delay_cs.to_le_bytes())?;
+        self.writer.write_all(&[0x00, 0x00])?;
+
+        // Image Descriptor con tabla de color local de 256 entradas
+        self.writer.write_all(&[0x2C, 0x00, 0x00, 0x00, 0x00])?;
+        self.writer.write_all(&(self.width as u16).to_le_bytes())?;
+        self.writer.write_all(&(self.height as u16).to_le_bytes())?;
+        self.writer.write_all(&[0x80 | 0x07])?;
+        for color in palette.iter() {
+            self.writer.write_all(&[
+                ((color >> 16) & 0xFF) as u8,
+                ((color >> 8) & 0xFF) as u8,
+                (color & 0xFF) as u8,
+            ])?;
+        }
+
+        self.writer.write_all(&[MIN_CODE_SIZE])?;
+        let data = lzw_encode(&indices);
+        for block in data.chunks(255) {
+            self.writer.write_all(&[block.len() as u8])?;
+            self.writer.write_all(block)?;
+        }
+        self.writer.write_all(&[0x00])
+    }
+
+    /// Cierra el GIF y devuelve el writer
+    pub fn finish(mut self) -> io::Result<W> {
+        self.writer.write_all(&[0x3B])?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+// Clave de 15 bits (5 bits por canal) usada para el histograma
+fn color_key(pixel: u32) -> usize {
+    let r = (pixel >> 19) & 0x1F;
+    let g = (pixel >> 11) & 0x1F;
+    let b = (pixel >> 3) & 0x1F;
+    ((r << 10) | (g << 5) | b) as usize
+}
+
+fn key_channel(key: usize, channel: usize) -> usize {
+    (key >> (10 - channel * 5)) & 0x1F
+}
+
+/// Reduce el frame a 256 colores con median cut sobre un histograma de 15 bits.
+/// Devuelve la paleta y el índice de paleta de cada pixel.
+fn quantize(pixels: &[u32]) -> ([u32; MAX_COLORS], Vec<u8>) {
+    // Además del conteo se acumula el color real de cada celda,
+    // así la paleta no pierde los 3 bits bajos de cada canal
+    let mut histogram = vec![0u32; 1 << 15];
+    let mut color_sums = vec![[0u64; 3]; 1 << 15];
+    for &pixel in pixels {
+        let key = color_key(pixel);
+        histogram[key] += 1;
+        color_sums[key][0] += ((pixel >> 16) & 0xFF) as u64;
+        color_sums[key][1] += ((pixel >> 8) & 0xFF) as u64;
+        color_sums[key][2] += (pixel & 0xFF) as u64;
+    }
+
+    let entries: Vec<usize> = (0..histogram.len()).filter(|&key| histogram[key] > 0).collect();
+    let mut boxes = vec![entries];
+
+    // Divide siempre la caja con más pixeles que todavía se pueda dividir
+    while boxes.len() < MAX_COLORS {
+        let candidate = boxes
+            .iter()
+            .enumerate()
+            .filter(|(_, entries)| entries.len() > 1)
+            .max_by_key(|(_, entries)| entries.iter().map(|&key| histogram[key] as u64).sum::<u64>())
+            .map(|(index, _)| index);
+
+        let Some(index) = candidate else { break };
+        let mut entries = boxes.swap_remove(index);
+
+        // Eje con mayor rango
+        let channel = (0..3)
+            .max_by_key(|&channel| {
+                let min = entries.iter().map(|&key| key_channel(key, channel)).min().unwrap_or(0);
+                let max = entries.iter().map(|&key| key_channel(key, channel)).max().unwrap_or(0);
+                max - min
+            })
+            .unwrap_or(0);
+        entries.sort_unstable_by_key(|&key| key_channel(key, channel));
+
+        // Corta en la mediana ponderada por cantidad de pixeles
+        let total: u64 = entries.iter().map(|&key| histogram[key] as u64).sum();
+        let mut accumulated = 0u64;
+        let mut split = entries.len() / 2;
+        for (i, &key) in entries.iter().enumerate() {
+            accumulated += histogram[key] as u64;
+            if accumulated * 2 >= total {
+                split = (i + 1).clamp(1, entries.len() - 1);
+                break;
+            }
+        }
+
+        let upper = entries.split_off(split);
+        boxes.push(entries);
+        boxes.push(upper);
+    }
+
+    let mut palette = [0u32; MAX_COLORS];
+    let mut lookup = vec![0u8; 1 << 15];
+    for (index, entries) in boxes.iter().enumerate() {
+        let mut sum = [0u64; 3];
+        let mut count = 0u64;
+        for &key in entries {
+            for (total, value) in sum.iter_mut().zip(color_sums[key]) {
+                *total += value;
+            }
+            count += histogram[key] as u64;
+            lookup[key] = index as u8;
+        }
+        let count = count.max(1);
+        let r = ((sum[0] + count / 2) / count) as u32;
+        let g = ((sum[1] + count / 2) / count) as u32;
+        let b = ((sum[2] + count / 2) / count) as u32;
+        palette[index] = (r << 16) | (g << 8) | b;
+    }
+
+    let indices = pixels.iter().map(|&pixel| lookup[color_key(pixel)]).collect();
+    (palette, indices)
+}
+
+// Escritor de códigos de ancho variable, bit menos significativo primero
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u32,
+    bits: u8,
+}
+
+impl BitWriter {
+    fn write(&mut self, code: u16, size: u8) {
+        self.buffer |= (code as u32) << self.bits;
+        self.bits += size;
+        while self.bits >= 8 {
+            self.bytes.push((self.buffer & 0xFF) as u8);
+            self.buffer >>= 8;
+            self.bits -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bits > 0 {
+            self.bytes.push((self.buffer & 0xFF) as u8);
+        }
+        self.bytes
+    }
+}
+
+fn lzw_encode(indices: &[u8]) -> Vec<u8> {
+    let mut output = BitWriter { bytes: Vec::new(), buffer: 0, bits: 0 };
+    // Tabla plana (prefijo, índice) -> código; 0 significa "sin entrada"
+    let mut dictionary = vec![0u16; (MAX_CODE as usize + 1) * MAX_COLORS];
+    let mut next_code = END_CODE + 1;
+    let mut code_size = MIN_CODE_SIZE + 1;
+
+    output.write(CLEAR_CODE, code_size);
+
+    let Some((&first, rest)) = indices.split_first() else {
+        output.write(END_CODE, code_size);
+        return output.finish();
+    };
+
+    let mut prefix = first as u16;
+    for &index in rest {
+        let slot = prefix as usize * MAX_COLORS + index as usize;
+        if dictionary[slot] != 0 {
+            prefix = dictionary[slot];
+            continue;
+        }
+
+        output.write(prefix, code_size);
+        if next_code >= (1 << code_size) && code_size < 12 {
+            code_size += 1;
+        }
+
+        if next_code >= MAX_CODE {
+            // Diccionario lleno: reinicia
+            output.write(CLEAR_CODE, code_size);
+            dictionary.fill(0);
+            next_code = END_CODE + 1;
+            code_size = MIN_CODE_SIZE + 1;
+        } else {
+            dictionary[slot] = next_code;
+            next_code += 1;
+        }
+
+        prefix = index as u16;
+    }
+
+    output.write(prefix, code_size);
+    if next_code >= (1 << code_size) && code_size < 12 {
+        code_size += 1;
+    }
+    output.write(END_CODE, code_size);
+    output.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Decodificador LZW mínimo de GIF: devuelve los índices y cuántos CLEAR leyó
+    fn lzw_decode(data: &[u8]) -> (Vec<u8>, usize) {
+        let mut output = Vec::new();
+        let mut clears = 0;
+        let mut dictionary: Vec<Vec<u8>> = Vec::new();
+        let mut previous: Option<Vec<u8>> = None;
+        let mut code_size = MIN_CODE_SIZE + 1;
+        let (mut buffer, mut bits, mut position) = (0u32, 0u8, 0);
+
+        loop {
+            while bits < code_size {
+                buffer |= (data[position] as u32) << bits;
+                position += 1;
+                bits += 8;
+            }
+            let code = (buffer & ((1 << code_size) - 1)) as u16;
+            buffer >>= code_size;
+            bits -= code_size;
+
+            if code == CLEAR_CODE {
+                clears += 1;
+                dictionary = (0..=END_CODE).map(|c| vec![c as u8]).collect();
+                previous = None;
+                code_size = MIN_CODE_SIZE + 1;
+                continue;
+            }
+            if code == END_CODE {
+                return (output, clears);
+            }
+
+            let entry = match (dictionary.get(code as usize), &previous) {
+                (Some(entry), _) => entry.clone(),
+                // Código que aún no está en la tabla: el prefijo anterior más su primer índice
+                (None, Some(prev)) if code as usize == dictionary.len() => {
+                    [prev.as_slice(), &prev[..1]].concat()
+                }
+                _ => panic!("código {code} fuera del diccionario"),
+            };
+            output.extend_from_slice(&entry);
+
+            if let Some(prev) = previous
+                && dictionary.len() <= MAX_CODE as usize
+            {
+                dictionary.push([prev.as_slice(), &entry[..1]].concat());
+            }
+            if dictionary.len() >= 1 << code_size && code_size < 12 {
+                code_size += 1;
+            }
+            previous = Some(entry);
+        }
+    }
+
+    #[test]
+    fn lzw_round_trip_empty() {
+        assert_eq!(lzw_decode(&lzw_encode(&[])), (Vec::new(), 1));
+    }
+
+    #[test]
+    fn lzw_round_trip_repeated_runs() {
+        // Pocas cadenas largas: ejercita el caso del código que aún no está en la tabla
+        let indices: Vec<u8> = (0..5000).map(|i| (i / 37 % 3) as u8).collect();
+        let (decoded, clears) = lzw_decode(&lzw_encode(&indices));
+        assert_eq!(decoded, indices);
+        assert_eq!(clears, 1);
+    }
+
+    #[test]
+    fn lzw_round_trip_resets_full_dictionary() {
+        // Índices pseudoaleatorios: casi cada código agrega una entrada y el diccionario se
+        // llena (4095 códigos) varias veces
+        let mut state = 0x2545_f491u32;
+        let indices: Vec<u8> = (0..50_000)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                (state >> 24) as u8
+            })
+            .collect();
+        let (decoded, clears) = lzw_decode(&lzw_encode(&indices));
+        assert_eq!(decoded, indices);
+        assert!(clears > 2, "se esperaban reinicios del diccionario, hubo {clears} CLEAR");
+    }
+}
@@ -0,0 +1,43 @@
+// headless.rs
+// Renderizado sin ventana: corre el mismo loop de render sobre el framebuffer
+// y graba los frames a disco
+
+use std::io;
+
+use crate::framebuffer::Framebuffer;
+use crate::recorder::{Recorder, RecordingSettings};
+use crate::{render_frame, Meshes, RenderSettings, Scene};
+
+/// Renderiza `frames` frames de la escena en `framebuffer`, los reescala a `output`
+/// (con el HUD encima) y los graba según `settings`
+pub fn run(
+    framebuffer: &mut Framebuffer,
+    output: &mut Framebuffer,
+    scene: &mut Scene,
+    meshes: &Meshes,
+    render_settings: &RenderSettings,
+    frames: usize,
+    settings: &RecordingSettings,
+) -> io::Result<()> {
+    let mut recorder = Recorder::start(settings, None, output.width, output.height)?;
+
+    // Los mensajes van a stderr: stdout puede estar transportando el video
+    eprintln!(
+        "🎞️  Renderizando {} frames sin ventana en {}",
+        frames,
+        recorder.path().display()
+    );
+
+    for _ in 0..frames {
+        framebuffer.clear();
+        scene.update();
+        render_frame(framebuffer, output, scene, meshes, render_settings);
+        recorder.capture(output)?;
+    }
+
+    let captured = recorder.captured();
+    recorder.finish()?;
+
+    eprintln!("✅ {} frames exportados", captured);
+    Ok(())
+}
@@ -0,0 +1,235 @@
+// hud.rs
+// Interfaz sobre la escena (texto, líneas y paneles). Todo se dibuja en la capa de
+// overlay del framebuffer, que se compone encima de la imagen sin usar profundidad
+
+use crate::framebuffer::Framebuffer;
+
+const INSTRUCTIONS: [&str; 4] = [
+    "Presiona ESPACIO para pausar cuando los planetas estén en buenas posiciones",
+    "* Usa A/S para hacer zoom y capturar detalles",
+    "* Usa flechas para centrarte en cada planeta",
+    "* Presiona R para volver al centro y ver todo el sistema",
+];
+
+/// Dibuja las instrucciones en la esquina inferior izquierda, sobre un panel translúcido
+pub fn draw_hud(framebuffer: &mut Framebuffer) {
+    let instructions_y = framebuffer.height.saturating_sub(90);
+    let color_text = 0xFFFFFF;
+
+    let panel_width = INSTRUCTIONS.iter().map(|line| line.len()).max().unwrap_or(0) * 8 + 20;
+    let panel_height = INSTRUCTIONS.len() * 15 + 14;
+    fill_rect(framebuffer, 10, instructions_y.saturating_sub(8), panel_width, panel_height, 0x000010, 0.55);
+    let top = instructions_y.saturating_sub(8) as i32;
+    draw_line(framebuffer, 10, top, (10 + panel_width) as i32 - 1, top, 0x5060A0);
+
+    for (i, line) in INSTRUCTIONS.iter().enumerate() {
+        draw_text(framebuffer, 20, instructions_y + i * 15, line, color_text);
+    }
+}
+
+/// Rellena un rectángulo de la capa de interfaz con `color` y opacidad `alpha`
+pub fn fill_rect(framebuffer: &mut Framebuffer, x: usize, y: usize, width: usize, height: usize, color: u32, alpha: f32) {
+    for py in y..y + height {
+        for px in x..x + width {
+            framebuffer.overlay_point(px, py, color, alpha);
+        }
+    }
+}
+
+/// Dibuja una línea (Bresenham) en la capa de interfaz
+pub fn draw_line(framebuffer: &mut Framebuffer, x1: i32, y1: i32, x2: i32, y2: i32, color: u32) {
+    let dx = (x2 - x1).abs();
+    let dy = -(y2 - y1).abs();
+    let sx = if x1 < x2 { 1 } else { -1 };
+    let sy = if y1 < y2 { 1 } else { -1 };
+    let mut err = dx + dy;
+    let mut x = x1;
+    let mut y = y1;
+
+    loop {
+        if x >= 0 && y >= 0 {
+            framebuffer.overlay_point(x as usize, y as usize, color, 1.0);
+        }
+        if x == x2 && y == y2 {
+            break;
+        }
+        let e2 = 2 * err;
+        if e2 >= dy {
+            err += dy;
+            x += sx;
+        }
+        if e2 <= dx {
+            err += dx;
+            y += sy;
+        }
+    }
+}
+
+/// Dibuja texto simple en la capa de interfaz
+pub fn draw_text(framebuffer: &mut Framebuffer, x: usize, y: usize, text: &str, color: u32) {
+    // Por caracteres, no por bytes: una letra acentuada ocupa una sola celda
+    for (i, c) in text.chars().enumerate() {
+        if c == '\n' {
+            continue;
+        }
+        let cx = x + i * 8;
+        draw_char(framebuffer, cx, y, c, color);
+    }
+}
+
+/// Dibuja un solo carácter de 8x8; los que no son ASCII usan una letra parecida
+fn draw_char(framebuffer: &mut Framebuffer, x: usize, y: usize, c: char, color: u32) {
+    // Fuente monoespaciada básica de 8x8 (95 caracteres imprimibles)
+    const FONT: [[u8; 8]; 95] = [
+        [0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00], // ' '
+        [0x18,0x3C,0x3C,0x18,0x18,0x00,0x18,0x00], // '!'
+        [0x36,0x36,0x24,0x00,0x00,0x00,0x00,0x00], // '"'
+        [0x36,0x36,0x7F,0x36,0x7F,0x36,0x36,0x00], // '#'
+        [0x0C,0x3E,0x03,0x1E,0x30,0x1F,0x0C,0x00], // '$'
+        [0x00,0x63,0x33,0x18,0x0C,0x66,0x63,0x00], // '%'
+        [0x1C,0x36,0x1C,0x6E,0x3B,0x33,0x6E,0x00], // '&'
+        [0x06,0x06,0x04,0x00,0x00,0x00,0x00,0x00], // '''
+        [0x18,0x0C,0x06,0x06,0x06,0x0C,0x18,0x00], // '('
+        [0x06,0x0C,0x18,0x18,0x18,0x0C,0x06,0x00], // ')'
+        [0x00,0x66,0x3C,0xFF,0x3C,0x66,0x00,0x00], // '*'
+        [0x00,0x0C,0x0C,0x3F,0x0C,0x0C,0x00,0x00], // '+'
+        [0x00,0x00,0x00,0x00,0x00,0x0C,0x0C,0x18], // ','
+        [0x00,0x00,0x00,0x3F,0x00,0x00,0x00,0x00], // '-'
+        [0x00,0x00,0x00,0x00,0x00,0x0C,0x0C,0x00], // '.'
+        [0x60,0x30,0x18,0x0C,0x06,0x03,0x01,0x00], // '/'
+        [0x3E,0x63,0x73,0x7B,0x6F,0x67,0x3E,0x00], // '0'
+        [0x0C,0x0E,0x0F,0x0C,0x0C,0x0C,0x3F,0x00], // '1'
+        [0x1E,0x33,0x30,0x1C,0x06,0x33,0x3F,0x00], // '2'
+        [0x1E,0x33,0x30,0x1C,0x30,0x33,0x1E,0x00], // '3'
+        [0x38,0x3C,0x36,0x33,0x7F,0x30,0x78,0x00], // '4'
+        [0x3F,0x03,0x1F,0x30,0x30,0x33,0x1E,0x00], // '5'
+        [0x1C,0x06,0x03,0x1F,0x33,0x33,0x1E,0x00], // '6'
+        [0x3F,0x33,0x30,0x18,0x0C,0x0C,0x0C,0x00], // '7'
+        [0x1E,0x33,0x33,0x1E,0x33,0x33,0x1E,0x00], // '8'
+        [0x1E,0x33,0x33,0x3E,0x30,0x18,0x0E,0x00], // '9'
+        [0x00,0x0C,0x0C,0x00,0x00,0x0C,0x0C,0x00], // ':'
+        [0x00,0x0C,0x0C,0x00,0x00,0x0C,0x0C,0x18], // ';'
+        [0x18,0x0C,0x06,0x03,0x06,0x0C,0x18,0x00], // '<'
+        [0x00,0x00,0x3F,0x00,0x00,0x3F,0x00,0x00], // '='
+        [0x06,0x0C,0x18,0x30,0x18,0x0C,0x06,0x00], // '>'
+        [0x1E,0x33,0x30,0x18,0x0C,0x00,0x0C,0x00], // '?'
+        [0x3E,0x63,0x6F,0x6F,0x6F,0x03,0x1E,0x00], // '@'
+        [0x0C,0x1E,0x33,0x33,0x3F,0x33,0x33,0x00], // 'A'
+        [0x3F,0x66,0x66,0x3E,0x66,0x66,0x3F,0x00], // 'B'
+        [0x3C,0x66,0x03,0x03,0x03,0x66,0x3C,0x00], // 'C'
+        [0x1F,0x36,0x66,0x66,0x66,0x36,0x1F,0x00], // 'D'
+        [0x7F,0x46,0x16,0x1E,0x16,0x46,0x7F,0x00], // 'E'
+        [0x7F,0x46,0x16,0x1E,0x16,0x06,0x0F,0x00], // 'F'
+        [0x3C,0x66,0x03,0x03,0x73,0x66,0x7C,0x00], // 'G'
+        [0x33,0x33,0x33,0x3F,0x33,0x33,0x33,0x00], // 'H'
+        [0x1E,0x0C,0x0C,0x0C,0x0C,0x0C,0x1E,0x00], // 'I'
+        [0x78,0x30,0x30,0x30,0x33,0x33,0x1E,0x00], // 'J'
+        [0x67,0x66,0x36,0x1E,0x36,0x66,0x67,0x00], // 'K'
+        [0x0F,0x06,0x06,0x06,0x46,0x66,0x7F,0x00], // 'L'
+        [0x63,0x77,0x7F,0x7F,0x6B,0x63,0x63,0x00], // 'M'
+        [0x63,0x67,0x6F,0x7B,0x73,0x63,0x63,0x00], // 'N'
+        [0x3E,0x63,0x63,0x63,0x63,0x63,0x3E,0x00], // 'O'
+        [0x3F,0x66,0x66,0x3E,0x06,0x06,0x0F,0x00], // 'P'
+        [0x3E,0x63,0x63,0x63,0x6B,0x33,0x5E,0x00], // 'Q'
+        [0x3F,0x66,0x66,0x3E,0x36,0x66,0x67,0x00], // 'R'
+        [0x1E,0x33,0x03,0x1E,0x30,0x33,0x1E,0x00], // 'S'
+        [0x3F,0x2D,0x0C,0x0C,0x0C,0x0C,0x1E,0x00], // 'T'
+        [0x33,0x33,0x33,0x33,0x33,0x33,0x3E,0x00], // 'U'
+        [0x33,0x33,0x33,0x33,0x33,0x1E,0x0C,0x00], // 'V'
+        [0x63,0x63,0x63,0x6B,0x7F,0x77,0x63,0x00], // 'W'
+        [0x63,0x63,0x36,0x1C,0x1C,0x36,0x63,0x00], // 'X'
+        [0x33,0x33,0x33,0x1E,0x0C,0x0C,0x1E,0x00], // 'Y'
+        [0x7F,0x63,0x31,0x18,0x4C,0x66,0x7F,0x00], // 'Z'
+        [0x1E,0x06,0x06,0x06,0x06,0x06,0x1E,0x00], // '['
+        [0x03,0x06,0x0C,0x18,0x30,0x60,0x40,0x00], // '\'
+        [0x1E,0x18,0x18,0x18,0x18,0x18,0x1E,0x00], // ']'
+        [0x08,0x1C,0x36,0x63,0x00,0x00,0x00,0x00], // '^'
+        [0x00,0x00,0x00,0x00,0x00,0x00,0x00,0xFF], // '_'
+        [0x0C,0x0C,0x18,0x00,0x00,0x00,0x00,0x00], // '`'
+        [0x00,0x00,0x1E,0x30,0x3E,0x33,0x6E,0x00], // 'a'
+        [0x07,0x06,0x06,0x3E,0x66,0x66,0x3B,0x00], // 'b'
+        [0x00,0x00,0x1E,0x33,0x03,0x33,0x1E,0x00], // 'c'
+        [0x38,0x30,0x30,0x3E,0x33,0x33,0x6E,0x00], // 'd'
+        [0x00,0x00,0x1E,0x33,0x3F,0x03,0x1E,0x00], // 'e'
+        [0x1C,0x36,0x06,0x0F,0x06,0x06,0x0F,0x00], // 'f'
+        [0x00,0x00,0x6E,0x33,0x33,0x3E,0x30,0x1F], // 'g'
+        [0x07,0x06,0x36,0x6E,0x66,0x66,0x67,0x00], // 'h'
+        [0x0C,0x00,0x0E,0x0C,0x0C,0x0C,0x1E,0x00], // 'i'
+        [0x30,0x00,0x38,0x30,0x30,0x33,0x33,0x1E], // 'j'
+        [0x07,0x06,0x66,0x36,0x1E,0x36,0x67,0x00], // 'k'
+        [0x0E,0x0C,0x0C,0x0C,0x0C,0x0C,0x1E,0x00], // 'l'
+        [0x00,0x00,0x33,0x7F,0x7F,0x6B,0x63,0x00], // 'm'
+        [0x00,0x00,0x1F,0x33,0x33,0x33,0x33,0x00], // 'n'
+        [0x00,0x00,0x1E,0x33,0x33,0x33,0x1E,0x00], // 'o'
+        [0x00,0x00,0x3B,0x66,0x66,0x3E,0x06,0x0F], // 'p'
+        [0x00,0x00,0x6E,0x33,0x33,0x3E,0x30,0x78], // 'q'
+        [0x00,0x00,0x3B,0x6E,0x66,0x06,0x0F,0x00], // 'r'
+        [0x00,0x00,0x3E,0x03,0x1E,0x30,0x1F,0x00], // 's'
+        [0x08,0x0C,0x3E,0x0C,0x0C,0x2C,0x18,0x00], // 't'
+        [0x00,0x00,0x33,0x33,0x33,0x33,0x6E,0x00], // 'u'
+        [0x00,0x00,0x33,0x33,0x33,0x1E,0x0C,0x00], // 'v'
+        [0x00,0x00,0x63,0x6B,0x7F,0x7F,0x36,0x00], // 'w'
+        [0x00,0x00,0x63,0x36,0x1C,0x36,0x63,0x00], // 'x'
+        [0x00,0x00,0x33,0x33,0x33,0x3E,0x30,0x1F], // 'y'
+        [0x00,0x00,0x3F,0x19,0x0C,0x26,0x3F,0x00], // 'z'
+        [0x38,0x0C,0x0C,0x07,0x0C,0x0C,0x38,0x00], // '{'
+        [0x0C,0x0C,0x0C,0x00,0x0C,0x0C,0x0C,0x00], // '|'
+        [0x07,0x0C,0x0C,0x38,0x0C,0x0C,0x07,0x00], // '}'
+        [0x6E,0x3B,0x00,0x00,0x00,0x00,0x00,0x00], // '~'
+    ];
+
+    // La fuente solo tiene ASCII: las letras acentuadas se dibujan sin el acento y el resto
+    // de caracteres fuera de rango, con '?'
+    let c = if (' '..='~').contains(&c) { c } else { ascii_fallback(c) };
+    let index = c as usize - 32;
+    let bitmap = FONT[index];
+    for (row, bits) in bitmap.iter().enumerate() {
+        for col in 0..8 {
+            if bits & (1 << col) != 0 {
+                framebuffer.overlay_point(x + col, y + row, color, 1.0);
+            }
+        }
+    }
+}
+
+// Letra ASCII que sustituye a un carácter que no está en la fuente
+fn ascii_fallback(c: char) -> char {
+    match c {
+        'á' | 'à' | 'ä' | 'â' => 'a',
+        'é' | 'è' | 'ë' | 'ê' => 'e',
+        'í' | 'ì' | 'ï' | 'î' => 'i',
+        'ó' | 'ò' | 'ö' | 'ô' => 'o',
+        'ú' | 'ù' | 'ü' | 'û' => 'u',
+        'ñ' => 'n',
+        'Á' | 'À' | 'Ä' | 'Â' => 'A',
+        'É' | 'È' | 'Ë' | 'Ê' => 'E',
+        'Í' | 'Ì' | 'Ï' | 'Î' => 'I',
+        'Ó' | 'Ò' | 'Ö' | 'Ô' => 'O',
+        'Ú' | 'Ù' | 'Ü' | 'Û' => 'U',
+        'Ñ' => 'N',
+        '°' => 'o',
+        _ => '?',
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(text: &str) -> Vec<u32> {
+        let mut framebuffer = Framebuffer::new(120, 16);
+        draw_text(&mut framebuffer, 0, 0, text, 0xFFFFFF);
+        framebuffer.overlay
+    }
+
+    #[test]
+    fn accented_letters_draw_as_their_base_letter() {
+        assert_eq!(render("estén órbita"), render("esten orbita"));
+        assert_eq!(render("Volcánico Ñ"), render("Volcanico N"));
+    }
+
+    #[test]
+    fn unknown_characters_draw_a_fallback_glyph() {
+        assert_eq!(render("a★b"), render("a?b"));
+    }
+}
@@ -0,0 +1,24 @@
+// light.rs
+// Luces de la escena, tal como las reciben los fragment shaders
+
+use nalgebra_glm::Vec3;
+
+use crate::color::LinearColor;
+
+// Luz puntual en coordenadas de mundo. `color` ya incluye la intensidad
+#[derive(Debug, Clone, Copy)]
+pub struct Light {
+    pub position: Vec3,
+    pub color: LinearColor,
+}
+
+impl Light {
+    pub fn new(position: Vec3, color: LinearColor) -> Self {
+        Light { position, color }
+    }
+
+    /// Dirección normalizada desde `point` hacia la luz (cero si `point` está sobre la luz)
+    pub fn direction_from(&self, point: &Vec3) -> Vec3 {
+        (self.position - point).try_normalize(1e-6).unwrap_or_else(Vec3::zeros)
+    }
+}
@@ -1,4 +1,4 @@
-use nalgebra_glm::{Vec3, Mat4};
+use nalgebra_glm::{Vec3, Vec4, Mat4};
 use minifb::{Key, Window, WindowOptions, ScaleMode};
 use std::time::Duration;
 use std::f32::consts::PI;
@@ -12,30 +12,357 @@ mod color;
 mod fragment;
 mod shaders;
 mod shader;
-
-use framebuffer::Framebuffer;
+mod export;
+mod headless;
+mod gif;
+mod recorder;
+mod options;
+mod y4m;
+mod bloom;
+mod raster;
+mod debug;
+mod hud;
+mod star;
+mod clipping;
+mod bench;
+mod light;
+mod params;
+mod generator;
+mod noise;
+
+use framebuffer::{BlendMode, Framebuffer};
+use clipping::{Clipped, Polygon};
+use bloom::Bloom;
+use debug::DebugView;
+use star::Star;
 use vertex::Vertex;
 use obj::Obj;
-use triangle::{triangle, ShaderType};
-use shaders::vertex_shader;
+use triangle::{is_back_facing, triangle, Interpolation};
+use shaders::{perspective_divide, vertex_shader, VertexShader};
+use light::Light;
+use params::RockyParams;
+use shader::{BodyParams, FragmentShader, GasGiantShader, IcePlanetShader, MoonShader, RingShader, RockyPlanetShader, SunShader};
+use options::Options;
+use recorder::Recorder;
 use crate::line::line;
 
-pub struct Uniforms {
+// Centro del sistema (donde está el sol)
+const SUN_CENTER_X: f32 = 600.0;
+const SUN_CENTER_Y: f32 = 450.0;
+
+// Resolución para la que está pensada la escena; otros tamaños la escalan y la centran
+const BASE_WIDTH: usize = 1200;
+const BASE_HEIGHT: usize = 800;
+
+// Zoom mínimo de la cámara (A aleja hasta aquí)
+const MIN_ZOOM: f32 = 0.3;
+
+// Escalas de render internas que recorren las teclas [ y ]
+const RENDER_SCALES: [f32; 6] = [0.25, 0.5, 0.75, 1.0, 1.5, 2.0];
+
+// Paso fijo de simulación por frame
+const TIME_STEP: f32 = 0.016;
+
+// Segmentos de la malla de los anillos
+const RING_SEGMENTS: usize = 96;
+
+pub struct Uniforms<'a> {
     model_matrix: Mat4,
+    projection_matrix: Mat4,
+    viewport_matrix: Mat4,
+    interpolation: Interpolation,
+    // Tiempo de la simulación, para animar los shaders
+    time: f32,
+    // Posición de la cámara en coordenadas homogéneas; con w = 0 es la dirección hacia una
+    // cámara en el infinito (proyección ortográfica)
+    eye: Vec4,
+    // Luces de la escena (la estrella)
+    lights: &'a [Light],
+    // Parámetros del cuerpo que se está dibujando
+    params: &'a BodyParams,
+}
+
+#[cfg(test)]
+impl<'a> Uniforms<'a> {
+    /// Uniforms de las pruebas del rasterizador: matrices identidad (los vértices ya vienen en
+    /// pixeles), cámara ortográfica y sin luces
+    pub fn for_tests(params: &'a BodyParams) -> Self {
+        Uniforms {
+            model_matrix: Mat4::identity(),
+            projection_matrix: Mat4::identity(),
+            viewport_matrix: Mat4::identity(),
+            interpolation: Interpolation::Perspective,
+            time: 0.0,
+            eye: Vec4::new(0.0, 0.0, -1.0, 0.0),
+            lights: &[],
+            params,
+        }
+    }
 }
 
-struct CelestialBody {
+pub struct CelestialBody {
     orbital_radius: f32,
     orbital_angle: f32,
     orbital_speed: f32,
     scale: f32,
     visible_radius: f32, 
     rotation: Vec3,
-    shader_type: ShaderType,
+    shader: Box<dyn FragmentShader>,
+    params: BodyParams,
     rotation_speed: f32,
     name: &'static str,
+    moons: Vec<Moon>,
+    rings: Option<Rings>,
+}
+
+impl CelestialBody {
+    /// Posición en el sistema (coordenadas polares alrededor del sol)
+    fn orbit_position(&self) -> (f32, f32) {
+        (
+            SUN_CENTER_X + self.orbital_radius * self.orbital_angle.cos(),
+            SUN_CENTER_Y + self.orbital_radius * self.orbital_angle.sin(),
+        )
+    }
+}
+
+/// Luna que orbita a un planeta
+pub struct Moon {
+    orbital_radius: f32, // distancia desde el planeta
+    orbital_angle: f32,
+    orbital_speed: f32,
+    scale: f32,
+    shader: MoonShader,
+    params: BodyParams,
+}
+
+/// Anillos semitransparentes alrededor de un planeta
+pub struct Rings {
+    // Corona plana con los radios de los anillos, en radios del planeta
+    mesh: Vec<Vertex>,
+    tilt: f32, // inclinación hacia la cámara (radianes)
+    shader: RingShader,
+    params: BodyParams,
+}
+
+impl Rings {
+    pub fn new(inner_radius: f32, outer_radius: f32, tilt: f32, shader: RingShader) -> Self {
+        Rings {
+            mesh: create_ring_vertices(inner_radius, outer_radius, RING_SEGMENTS),
+            tilt,
+            shader,
+            // Los anillos son planos: se ven iluminados desde ambos lados
+            params: BodyParams::new(0.6, 0.0),
+        }
+    }
+}
+
+/// Geometría compartida por todos los cuerpos
+pub struct Meshes {
+    sphere: Vec<Vertex>,
+}
+
+/// Opciones de render que no forman parte de la simulación
+pub struct RenderSettings {
+    ring_blend: BlendMode,
+    // Factor de supersampling por eje (1 = sin antialiasing)
+    supersampling: usize,
+    // Hilos de rasterización (1 = camino serial)
+    threads: usize,
+    // Resolución interna relativa a la ventana (0.5 = mitad, 2.0 = doble)
+    render_scale: f32,
+    // Muestra las instrucciones en pantalla
+    show_hud: bool,
+    // Campo de visión vertical en grados (0 = proyección ortográfica)
+    fov: f32,
+    interpolation: Interpolation,
+}
+
+impl RenderSettings {
+    /// Tamaño del framebuffer de render para una salida de `width` x `height`
+    pub fn render_size(&self, width: usize, height: usize) -> (usize, usize) {
+        let scaled = |size: usize| ((size as f32 * self.render_scale).round() as usize).max(1) * self.supersampling;
+        (scaled(width), scaled(height))
+    }
+}
+
+// Ajusta el framebuffer de render al tamaño de salida y a la escala actual
+fn fit_render_target(framebuffer: &mut Framebuffer, settings: &RenderSettings, width: usize, height: usize) {
+    let (render_width, render_height) = settings.render_size(width, height);
+    framebuffer.resize(render_width, render_height);
+    // Niveles extra para que el halo mantenga su tamaño en pantalla
+    let growth = (render_height as f32 / BASE_HEIGHT as f32).log2().round().max(0.0) as usize;
+    framebuffer.bloom.levels = Bloom::new().levels + growth;
+}
+
+// Siguiente escala de RENDER_SCALES hacia arriba o hacia abajo
+fn step_render_scale(current: f32, up: bool) -> f32 {
+    if up {
+        RENDER_SCALES.iter().copied().find(|&scale| scale > current + 1e-3).unwrap_or(current)
+    } else {
+        RENDER_SCALES.iter().rev().copied().find(|&scale| scale < current - 1e-3).unwrap_or(current)
+    }
+}
+
+/// Estado completo de la simulación: cuerpos, tiempo y cámara
+pub struct Scene {
+    bodies: Vec<CelestialBody>,
+    star: Star,
+    time: f32,
+    camera_zoom: f32,
+    camera_x: f32,
+    camera_y: f32,
+    paused: bool,
 }
 
+impl Scene {
+    pub fn new() -> Self {
+        // Define los cuerpos celestes con órbitas
+        let bodies = vec![
+            CelestialBody {
+                orbital_radius: 250.0,
+                orbital_angle: 0.0,
+                orbital_speed: 0.02,
+                scale: 80.0,
+                visible_radius: 80.0 / 2.0, // planeta rocoso sin anillos
+                rotation: Vec3::new(0.0, 0.0, 0.0),
+                shader: Box::new(RockyPlanetShader { params: RockyParams::earth() }),
+                params: BodyParams::new(0.2, 0.6), // atmósfera densa
+                rotation_speed: 0.015,
+                name: "Planeta Rocoso",
+                moons: vec![Moon {
+                    orbital_radius: 100.0,
+                    orbital_angle: 0.0,
+                    orbital_speed: 0.05,
+                    scale: 40.0,
+                    shader: MoonShader::default(),
+                    params: BodyParams::new(0.15, 0.0),
+                }],
+                rings: None,
+            },
+            CelestialBody {
+                orbital_radius: 480.0,
+                orbital_angle: std::f32::consts::PI,
+                orbital_speed: 0.01,
+                scale: 130.0,
+                // Los anillos van de 160 a 220 px del centro: el espacio reservado es su borde
+                // exterior, que ya cubre el planeta
+                visible_radius: 220.0,
+                rotation: Vec3::new(0.0, 0.0, 0.0),
+                shader: Box::new(GasGiantShader::default()),
+                params: BodyParams::default(),
+                rotation_speed: 0.012,
+                name: "Gigante Gaseoso",
+                moons: Vec::new(),
+                // Anillos semitransparentes: dejan ver el planeta detrás
+                rings: Some(Rings::new(160.0 / 130.0, 220.0 / 130.0, 0.45, RingShader::default())),
+            },
+            CelestialBody {
+                orbital_radius: 620.0,
+                orbital_angle: std::f32::consts::PI * 1.5,
+                orbital_speed: 0.007,
+                scale: 100.0,
+                visible_radius: 100.0 / 2.0,
+                rotation: Vec3::new(0.0, 0.0, 0.0),
+                shader: Box::new(IcePlanetShader::default()),
+                params: BodyParams::new(0.3, 0.4), // el hielo refleja más luz
+                rotation_speed: 0.01,
+                name: "Planeta Helado",
+                moons: Vec::new(),
+                rings: None,
+            },
+            // Rocoso como el primero, con otros parámetros, en la órbita más lejana
+            CelestialBody {
+                orbital_radius: 760.0,
+                orbital_angle: std::f32::consts::PI * 0.25,
+                orbital_speed: 0.005,
+                scale: 60.0,
+                visible_radius: 60.0 / 2.0,
+                rotation: Vec3::new(0.0, 0.0, 0.0),
+                shader: Box::new(RockyPlanetShader { params: RockyParams { seed: 7, ..RockyParams::mars() } }),
+                params: BodyParams::new(0.2, 0.15), // atmósfera tenue
+                rotation_speed: 0.02,
+                name: "Planeta Rojo",
+                moons: Vec::new(),
+                rings: None,
+            },
+        ];
+
+        Self::with_bodies(bodies)
+    }
+
+    /// Sistema generado a partir de una semilla: la misma semilla da el mismo sistema
+    pub fn from_seed(seed: u64) -> Self {
+        Self::with_bodies(generator::generate_bodies(seed))
+    }
+
+    /// Reemplaza los planetas por los de otra semilla; la cámara, la estrella y el tiempo se mantienen
+    pub fn regenerate(&mut self, seed: u64) {
+        self.bodies = Self::from_seed(seed).bodies;
+    }
+
+    fn with_bodies(mut bodies: Vec<CelestialBody>) -> Self {
+
+        // Evitar colisiones ajustando las distancias orbitales
+        for i in 1..bodies.len() {
+            let prev = &bodies[i - 1];
+            let min_distance = prev.orbital_radius + prev.visible_radius + bodies[i].visible_radius + 40.0; // 40px de margen
+            if bodies[i].orbital_radius < min_distance {
+                bodies[i].orbital_radius = min_distance;
+            }
+        }
+
+        let mut scene = Scene {
+            bodies,
+            star: Star::sun(),
+            time: 0.0,
+            camera_zoom: 1.0,
+            camera_x: 0.0,
+            camera_y: 0.0,
+            paused: false,
+        };
+        scene.reset_camera();
+        scene
+    }
+
+    /// Cámara que muestra todo el sistema: el sol al centro de la pantalla y la órbita más
+    /// lejana (con sus anillos y lunas) dentro de ella. Nunca acerca más que el zoom normal
+    pub fn reset_camera(&mut self) {
+        let extent = self
+            .bodies
+            .iter()
+            .map(|body| body.orbital_radius + body.visible_radius)
+            .fold(0.0, f32::max);
+        let half_screen = BASE_WIDTH.min(BASE_HEIGHT) as f32 / 2.0;
+        // El zoom escala alrededor del origen: el desplazamiento devuelve el sol al centro
+        self.camera_zoom = (half_screen / extent).clamp(MIN_ZOOM, 1.0);
+        self.camera_x = BASE_WIDTH as f32 / 2.0 - SUN_CENTER_X * self.camera_zoom;
+        self.camera_y = BASE_HEIGHT as f32 / 2.0 - SUN_CENTER_Y * self.camera_zoom;
+    }
+
+    /// Avanza la simulación un paso fijo (no hace nada si está en pausa)
+    pub fn update(&mut self) {
+        if self.paused {
+            return;
+        }
+
+        self.time += TIME_STEP;
+
+        // Update orbital positions and rotations
+        for body in &mut self.bodies {
+            body.orbital_angle += body.orbital_speed;
+            body.rotation.y += body.rotation_speed;
+            for moon in &mut body.moons {
+                moon.orbital_angle += moon.orbital_speed;
+            }
+        }
+    }
+}
+
+impl Default for Scene {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 fn create_model_matrix(translation: Vec3, scale: f32, rotation: Vec3) -> Mat4 {
     let (sin_x, cos_x) = rotation.x.sin_cos();
@@ -75,315 +402,368 @@ fn create_model_matrix(translation: Vec3, scale: f32, rotation: Vec3) -> Mat4 {
     transform_matrix * rotation_matrix
 }
 
-fn render(framebuffer: &mut Framebuffer, uniforms: &Uniforms, vertex_array: &[Vertex], shader_type: ShaderType, time: f32) {
-    // Vertex Shader Stage
-    let mut transformed_vertices = Vec::with_capacity(vertex_array.len());
+/// Lleva la caja de pixeles [0, width] x [0, height] x [-depth, depth] al cubo [-1, 1] del
+/// clip space, donde se recortan los triángulos. Con `fov` = 0 la proyección es ortográfica;
+/// si no, hay una cámara centrada frente a la pantalla con ese campo de visión vertical
+/// (en grados) y el plano z = 0 sigue cayendo en los mismos pixeles
+fn create_projection_matrix(width: f32, height: f32, depth: f32, fov: f32) -> Mat4 {
+    // w = 1 + z / distancia de la cámara: lo cercano (z < 0) se agranda
+    let perspective = if fov > 0.0 {
+        1.0 / (height / 2.0 / (fov.to_radians() / 2.0).tan())
+    } else {
+        0.0
+    };
+    Mat4::new(
+        2.0 / width, 0.0, 0.0, -1.0,
+        0.0, 2.0 / height, 0.0, -1.0,
+        0.0, 0.0, 1.0 / depth, 0.0,
+        0.0, 0.0, perspective, 1.0,
+    )
+}
+
+/// Cámara de `create_projection_matrix`: frente al centro de la pantalla a la distancia que da
+/// el `fov`, o en el infinito (w = 0) si la proyección es ortográfica
+fn create_eye(width: f32, height: f32, fov: f32) -> Vec4 {
+    if fov > 0.0 {
+        let distance = height / 2.0 / (fov.to_radians() / 2.0).tan();
+        Vec4::new(width / 2.0, height / 2.0, -distance, 1.0)
+    } else {
+        Vec4::new(0.0, 0.0, -1.0, 0.0)
+    }
+}
+
+/// Inversa de `create_projection_matrix`: de coordenadas normalizadas a pixeles y profundidad
+fn create_viewport_matrix(width: f32, height: f32, depth: f32) -> Mat4 {
+    Mat4::new(
+        width / 2.0, 0.0, 0.0, width / 2.0,
+        0.0, height / 2.0, 0.0, height / 2.0,
+        0.0, 0.0, depth, 0.0,
+        0.0, 0.0, 0.0, 1.0,
+    )
+}
+
+/// Genera un anillo plano (corona circular) en el plano XZ, como lista de triángulos
+fn create_ring_vertices(inner_radius: f32, outer_radius: f32, segments: usize) -> Vec<Vertex> {
+    let mut vertices = Vec::with_capacity(segments * 6);
+    let normal = Vec3::new(0.0, 1.0, 0.0);
+    let point = |radius: f32, angle: f32| Vec3::new(radius * angle.cos(), 0.0, radius * angle.sin());
+
+    for i in 0..segments {
+        let theta1 = (i as f32 / segments as f32) * std::f32::consts::TAU;
+        let theta2 = ((i + 1) as f32 / segments as f32) * std::f32::consts::TAU;
+
+        let inner1 = Vertex::new(point(inner_radius, theta1), normal, nalgebra_glm::Vec2::new(0.0, 0.0));
+        let inner2 = Vertex::new(point(inner_radius, theta2), normal, nalgebra_glm::Vec2::new(1.0, 0.0));
+        let outer1 = Vertex::new(point(outer_radius, theta1), normal, nalgebra_glm::Vec2::new(0.0, 1.0));
+        let outer2 = Vertex::new(point(outer_radius, theta2), normal, nalgebra_glm::Vec2::new(1.0, 1.0));
+
+        vertices.extend([inner1.clone(), outer1, outer2.clone()]);
+        vertices.extend([inner1, outer2, inner2]);
+    }
+
+    vertices
+}
+
+fn render(
+    framebuffer: &mut Framebuffer,
+    uniforms: &Uniforms,
+    vertex_array: &[Vertex],
+    vertex_shader: &impl VertexShader,
+    fragment_shader: &dyn FragmentShader,
+    threads: usize,
+) {
+    // Vertex Shader Stage: cada vértice pasa a clip space y de una vez a pantalla; la división
+    // solo se usa en los triángulos que quedan completamente dentro del volumen de vista
+    let mut vertices = Vec::with_capacity(vertex_array.len());
     for vertex in vertex_array {
-        let transformed = vertex_shader(vertex, uniforms);
-        transformed_vertices.push(transformed);
+        let mut transformed = vertex_shader.shade(vertex, uniforms);
+        perspective_divide(&mut transformed, uniforms);
+        vertices.push(transformed);
     }
 
     // Primitive Assembly Stage
-    let mut triangles = Vec::new();
-    for i in (0..transformed_vertices.len()).step_by(3) {
-        if i + 2 < transformed_vertices.len() {
-            triangles.push([
-                transformed_vertices[i].clone(),
-                transformed_vertices[i + 1].clone(),
-                transformed_vertices[i + 2].clone(),
-            ]);
+    // Los triángulos son índices a `vertices`: los que quedan dentro del volumen de vista usan
+    // sus vértices sin copiarlos y los que cruzan un plano agregan al final los vértices del
+    // recorte. Salvo que la superficie se vea de ambos lados, se descartan los que dan la
+    // espalda a la cámara
+    let double_sided = fragment_shader.is_double_sided();
+    let mut triangles = Vec::with_capacity(vertex_array.len() / 3);
+    // Buffer del recorte, reutilizado por todos los triángulos
+    let mut polygon = Polygon::new();
+    for i in (0..vertex_array.len()).step_by(3) {
+        if i + 2 < vertex_array.len() {
+            match clipping::clip_triangle([&vertices[i], &vertices[i + 1], &vertices[i + 2]], &mut polygon) {
+                Clipped::Outside => {}
+                Clipped::Inside => push_front_facing(&mut triangles, &vertices, [i, i + 1, i + 2], double_sided),
+                Clipped::Polygon => {
+                    let first = vertices.len();
+                    for vertex in polygon.vertices() {
+                        let mut vertex = vertex.clone();
+                        perspective_divide(&mut vertex, uniforms);
+                        vertices.push(vertex);
+                    }
+                    for j in first + 1..vertices.len() - 1 {
+                        push_front_facing(&mut triangles, &vertices, [first, j, j + 1], double_sided);
+                    }
+                }
+            }
         }
     }
 
-    // Rasterization Stage
-    let mut fragments = Vec::new();
+    // Aristas para la vista de wireframe
+    if framebuffer.wireframe {
+        for tri in &triangles {
+            framebuffer.queue_wireframe(tri.map(|index| vertices[index].transformed_position));
+        }
+    }
+
+    // Con varios hilos se rasteriza por tiles en paralelo (mismo resultado que el serial)
+    if threads > 1 {
+        raster::render_tiled(framebuffer, &vertices, &triangles, fragment_shader, uniforms, threads);
+        return;
+    }
+
+    // Rasterization Stage: los fragmentos se escriben en el framebuffer a medida que se
+    // sombrean, así la prueba de profundidad temprana ve todo lo ya dibujado
     for tri in &triangles {
-        fragments.extend(triangle(&tri[0], &tri[1], &tri[2], shader_type, time));
+        let [a, b, c] = tri.map(|index| &vertices[index]);
+        triangle(a, b, c, fragment_shader, uniforms, framebuffer);
     }
+}
 
-    // Fragment Processing Stage
-    for fragment in fragments {
-        let x = fragment.position.x as usize;
-        let y = fragment.position.y as usize;
-        if x < framebuffer.width && y < framebuffer.height {
-            let color = fragment.color.to_hex();
-            framebuffer.set_current_color(color);
-            framebuffer.point(x, y, fragment.depth);
-        }
+// Agrega el triángulo `tri` (índices a `vertices`) salvo que dé la espalda a la cámara
+fn push_front_facing(triangles: &mut Vec<[usize; 3]>, vertices: &[Vertex], tri: [usize; 3], double_sided: bool) {
+    let [a, b, c] = tri.map(|index| &vertices[index].transformed_position);
+    if double_sided || !is_back_facing(a, b, c) {
+        triangles.push(tri);
     }
 }
 
-fn draw_line(framebuffer: &mut Framebuffer, x1: i32, y1: i32, x2: i32, y2: i32, color: u32) {
-    let dx = (x2 - x1).abs();
-    let dy = -(y2 - y1).abs();
-    let sx = if x1 < x2 { 1 } else { -1 };
-    let sy = if y1 < y2 { 1 } else { -1 };
-    let mut err = dx + dy;
-    let mut x = x1;
-    let mut y = y1;
-
-    loop {
-        if x >= 0 && y >= 0 && (x as usize) < framebuffer.width && (y as usize) < framebuffer.height {
-            framebuffer.set_current_color(color);
-            framebuffer.point(x as usize, y as usize, 0.0);
-        }
-        if x == x2 && y == y2 {
-            break;
+/// Dibuja un frame completo de la escena: sol, planetas, anillos y luna
+pub fn render_scene(framebuffer: &mut Framebuffer, scene: &Scene, meshes: &Meshes, settings: &RenderSettings) {
+    let vertex_array = &meshes.sphere;
+    let camera_zoom = scene.camera_zoom;
+    let time = scene.time;
+    let star = &scene.star;
+
+    // La escena se diseñó para BASE_WIDTH x BASE_HEIGHT: se escala para caber en el
+    // framebuffer (ventana, escala de render y supersampling) y se centra
+    let pixel_scale = (framebuffer.width as f32 / BASE_WIDTH as f32).min(framebuffer.height as f32 / BASE_HEIGHT as f32);
+    let offset_x = (framebuffer.width as f32 - BASE_WIDTH as f32 * pixel_scale) / 2.0;
+    let offset_y = (framebuffer.height as f32 - BASE_HEIGHT as f32 * pixel_scale) / 2.0;
+
+    // Pasa de coordenadas del sistema a pixeles del framebuffer
+    let to_screen = |x: f32, y: f32| {
+        Vec3::new(
+            (x * camera_zoom + scene.camera_x) * pixel_scale + offset_x,
+            (y * camera_zoom + scene.camera_y) * pixel_scale + offset_y,
+            0.0,
+        )
+    };
+
+    // Volumen de vista: la pantalla, con profundidad de sobra para cualquier cuerpo
+    let (width, height) = (framebuffer.width as f32, framebuffer.height as f32);
+    let depth = width.max(height);
+    let projection_matrix = create_projection_matrix(width, height, depth, settings.fov);
+    let viewport_matrix = create_viewport_matrix(width, height, depth);
+    let interpolation = settings.interpolation;
+    let eye = create_eye(width, height, settings.fov);
+
+    // La estrella ilumina desde su centro
+    let sun_position = to_screen(SUN_CENTER_X, SUN_CENTER_Y);
+    let lights = [Light::new(sun_position, star.light())];
+    let sun_params = BodyParams::default();
+    let uniforms_for = |model_matrix: Mat4, params| Uniforms {
+        model_matrix,
+        projection_matrix,
+        viewport_matrix,
+        interpolation,
+        time,
+        eye,
+        lights: &lights,
+        params,
+    };
+
+    // Render the SUN first (always at center)
+    let sun_matrix = create_model_matrix(
+        sun_position,
+        star.scale * camera_zoom * pixel_scale,
+        Vec3::new(0.0, time * 0.005, 0.0)
+    );
+    let sun_uniforms = uniforms_for(sun_matrix, &sun_params);
+    render(framebuffer, &sun_uniforms, vertex_array, &vertex_shader, &SunShader { star: *star }, settings.threads);
+
+    // Render all planets in their orbits
+    let margin = 300.0 * pixel_scale;
+    for body in &scene.bodies {
+        // Calculate orbital position using polar coordinates
+        let (orbit_x, orbit_y) = body.orbit_position();
+
+        // Apply camera transformations
+        let screen_position = to_screen(orbit_x, orbit_y);
+
+        // Only render if visible on screen (with generous margin)
+        if screen_position.x > -margin && screen_position.x < framebuffer.width as f32 + margin &&
+           screen_position.y > -margin && screen_position.y < framebuffer.height as f32 + margin {
+
+            let model_matrix = create_model_matrix(
+                screen_position,
+                body.scale * camera_zoom * pixel_scale,
+                body.rotation
+            );
+            let uniforms = uniforms_for(model_matrix, &body.params);
+
+            render(framebuffer, &uniforms, vertex_array, &vertex_shader, body.shader.as_ref(), settings.threads);
         }
-        let e2 = 2 * err;
-        if e2 >= dy {
-            err += dy;
-            x += sx;
+    }
+
+    // Dibuja los anillos semitransparentes de cada planeta
+    framebuffer.set_blend_mode(settings.ring_blend);
+    for body in &scene.bodies {
+        if let Some(rings) = &body.rings {
+            let (x, y) = body.orbit_position();
+            let ring_matrix = create_model_matrix(
+                to_screen(x, y),
+                body.scale * camera_zoom * pixel_scale,
+                Vec3::new(rings.tilt, 0.0, 0.0),
+            );
+            let ring_uniforms = uniforms_for(ring_matrix, &rings.params);
+            render(framebuffer, &ring_uniforms, &rings.mesh, &vertex_shader, &rings.shader, settings.threads);
         }
-        if e2 <= dx {
-            err += dx;
-            y += sy;
+    }
+    framebuffer.set_blend_mode(BlendMode::Opaque);
+
+    // Dibuja las lunas orbitando su planeta
+    for body in &scene.bodies {
+        let (planet_x, planet_y) = body.orbit_position();
+        for moon in &body.moons {
+            let moon_x = planet_x + moon.orbital_radius * moon.orbital_angle.cos();
+            let moon_y = planet_y + moon.orbital_radius * moon.orbital_angle.sin();
+
+            let moon_matrix = create_model_matrix(
+                to_screen(moon_x, moon_y),
+                moon.scale * camera_zoom * pixel_scale,
+                Vec3::new(0.0, moon.orbital_angle * 2.0, 0.0),
+            );
+            let moon_uniforms = uniforms_for(moon_matrix, &moon.params);
+            render(framebuffer, &moon_uniforms, vertex_array, &vertex_shader, &moon.shader, settings.threads);
         }
     }
-}
-
-fn draw_ring(framebuffer: &mut Framebuffer, center: Vec3, inner_radius: f32, outer_radius: f32, color: u32) {
-    let steps = 100;
-    for i in 0..steps {
-        let theta1 = (i as f32 / steps as f32) * std::f32::consts::TAU;
-        let theta2 = ((i + 1) as f32 / steps as f32) * std::f32::consts::TAU;
 
-        let x1_inner = center.x + inner_radius * theta1.cos();
-        let y1_inner = center.y + inner_radius * theta1.sin();
-        let x2_inner = center.x + inner_radius * theta2.cos();
-        let y2_inner = center.y + inner_radius * theta2.sin();
+    // Tone mapping del buffer HDR al buffer final de 8 bits
+    framebuffer.resolve();
+}
 
-        let x1_outer = center.x + outer_radius * theta1.cos();
-        let y1_outer = center.y + outer_radius * theta1.sin();
-        let x2_outer = center.x + outer_radius * theta2.cos();
-        let y2_outer = center.y + outer_radius * theta2.sin();
+/// Dibuja la escena, la reescala a `output` (tamaño de ventana) y compone el HUD encima
+pub fn render_frame(framebuffer: &mut Framebuffer, output: &mut Framebuffer, scene: &Scene, meshes: &Meshes, settings: &RenderSettings) {
+    render_scene(framebuffer, scene, meshes, settings);
+    framebuffer.resample(output);
 
-        draw_line(framebuffer, x1_inner as i32, y1_inner as i32, x2_inner as i32, y2_inner as i32, color);
-        draw_line(framebuffer, x1_outer as i32, y1_outer as i32, x2_outer as i32, y2_outer as i32, color);
+    output.clear_overlay();
+    if settings.show_hud {
+        hud::draw_hud(output);
     }
+    output.composite_overlay();
 }
 
+fn main() {
+    let frame_delay = Duration::from_millis(16);
 
-/// Dibuja texto simple en pantalla (blanco)
-fn draw_text(framebuffer: &mut Framebuffer, x: usize, y: usize, text: &str, color: u32) {
-    let bytes = text.as_bytes();
-    for (i, &b) in bytes.iter().enumerate() {
-        if b == b'\n' {
-            continue;
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let options = match Options::from_args(&args) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("❌ {}", message);
+            std::process::exit(2);
         }
-        let cx = x + i * 8;
-        draw_char(framebuffer, cx, y, b as char, color);
-    }
-}
+    };
+
+    let mut render_settings = RenderSettings {
+        ring_blend: options.ring_blend,
+        supersampling: options.supersampling(),
+        threads: options.threads,
+        render_scale: options.render_scale,
+        show_hud: true,
+        fov: options.fov,
+        interpolation: options.interpolation,
+    };
+
+    // Se renderiza a la escala interna (y supersampling) y se reescala a `output`,
+    // que tiene el tamaño de la ventana
+    let mut output = Framebuffer::new(BASE_WIDTH, BASE_HEIGHT);
+    let mut framebuffer = Framebuffer::new(BASE_WIDTH, BASE_HEIGHT);
+    framebuffer.set_background_color(0x000008); // Negro espacio profundo
+    framebuffer.set_tone_mapping(options.tone_mapping);
+    framebuffer.set_exposure(options.exposure);
+    framebuffer.bloom.enabled = options.bloom;
+    framebuffer.bloom.threshold = options.bloom_threshold;
+    framebuffer.bloom.strength = options.bloom_strength;
+    framebuffer.debug_view = options.debug_view;
+    framebuffer.wireframe = options.wireframe;
+    framebuffer.dither = options.dither;
+    framebuffer.early_depth_test = options.early_depth_test;
+    fit_render_target(&mut framebuffer, &render_settings, output.width, output.height);
 
-/// Dibuja un solo carácter ASCII de 8x8
-fn draw_char(framebuffer: &mut Framebuffer, x: usize, y: usize, c: char, color: u32) {
-    // Fuente monoespaciada básica de 8x8 (95 caracteres imprimibles)
-    const FONT: [[u8; 8]; 95] = [
-        [0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00], // ' '
-        [0x18,0x3C,0x3C,0x18,0x18,0x00,0x18,0x00], // '!'
-        [0x36,0x36,0x24,0x00,0x00,0x00,0x00,0x00], // '"'
-        [0x36,0x36,0x7F,0x36,0x7F,0x36,0x36,0x00], // '#'
-        [0x0C,0x3E,0x03,0x1E,0x30,0x1F,0x0C,0x00], // '$'
-        [0x00,0x63,0x33,0x18,0x0C,0x66,0x63,0x00], // '%'
-        [0x1C,0x36,0x1C,0x6E,0x3B,0x33,0x6E,0x00], // '&'
-        [0x06,0x06,0x04,0x00,0x00,0x00,0x00,0x00], // '''
-        [0x18,0x0C,0x06,0x06,0x06,0x0C,0x18,0x00], // '('
-        [0x06,0x0C,0x18,0x18,0x18,0x0C,0x06,0x00], // ')'
-        [0x00,0x66,0x3C,0xFF,0x3C,0x66,0x00,0x00], // '*'
-        [0x00,0x0C,0x0C,0x3F,0x0C,0x0C,0x00,0x00], // '+'
-        [0x00,0x00,0x00,0x00,0x00,0x0C,0x0C,0x18], // ','
-        [0x00,0x00,0x00,0x3F,0x00,0x00,0x00,0x00], // '-'
-        [0x00,0x00,0x00,0x00,0x00,0x0C,0x0C,0x00], // '.'
-        [0x60,0x30,0x18,0x0C,0x06,0x03,0x01,0x00], // '/'
-        [0x3E,0x63,0x73,0x7B,0x6F,0x67,0x3E,0x00], // '0'
-        [0x0C,0x0E,0x0F,0x0C,0x0C,0x0C,0x3F,0x00], // '1'
-        [0x1E,0x33,0x30,0x1C,0x06,0x33,0x3F,0x00], // '2'
-        [0x1E,0x33,0x30,0x1C,0x30,0x33,0x1E,0x00], // '3'
-        [0x38,0x3C,0x36,0x33,0x7F,0x30,0x78,0x00], // '4'
-        [0x3F,0x03,0x1F,0x30,0x30,0x33,0x1E,0x00], // '5'
-        [0x1C,0x06,0x03,0x1F,0x33,0x33,0x1E,0x00], // '6'
-        [0x3F,0x33,0x30,0x18,0x0C,0x0C,0x0C,0x00], // '7'
-        [0x1E,0x33,0x33,0x1E,0x33,0x33,0x1E,0x00], // '8'
-        [0x1E,0x33,0x33,0x3E,0x30,0x18,0x0E,0x00], // '9'
-        [0x00,0x0C,0x0C,0x00,0x00,0x0C,0x0C,0x00], // ':'
-        [0x00,0x0C,0x0C,0x00,0x00,0x0C,0x0C,0x18], // ';'
-        [0x18,0x0C,0x06,0x03,0x06,0x0C,0x18,0x00], // '<'
-        [0x00,0x00,0x3F,0x00,0x00,0x3F,0x00,0x00], // '='
-        [0x06,0x0C,0x18,0x30,0x18,0x0C,0x06,0x00], // '>'
-        [0x1E,0x33,0x30,0x18,0x0C,0x00,0x0C,0x00], // '?'
-        [0x3E,0x63,0x6F,0x6F,0x6F,0x03,0x1E,0x00], // '@'
-        [0x0C,0x1E,0x33,0x33,0x3F,0x33,0x33,0x00], // 'A'
-        [0x3F,0x66,0x66,0x3E,0x66,0x66,0x3F,0x00], // 'B'
-        [0x3C,0x66,0x03,0x03,0x03,0x66,0x3C,0x00], // 'C'
-        [0x1F,0x36,0x66,0x66,0x66,0x36,0x1F,0x00], // 'D'
-        [0x7F,0x46,0x16,0x1E,0x16,0x46,0x7F,0x00], // 'E'
-        [0x7F,0x46,0x16,0x1E,0x16,0x06,0x0F,0x00], // 'F'
-        [0x3C,0x66,0x03,0x03,0x73,0x66,0x7C,0x00], // 'G'
-        [0x33,0x33,0x33,0x3F,0x33,0x33,0x33,0x00], // 'H'
-        [0x1E,0x0C,0x0C,0x0C,0x0C,0x0C,0x1E,0x00], // 'I'
-        [0x78,0x30,0x30,0x30,0x33,0x33,0x1E,0x00], // 'J'
-        [0x67,0x66,0x36,0x1E,0x36,0x66,0x67,0x00], // 'K'
-        [0x0F,0x06,0x06,0x06,0x46,0x66,0x7F,0x00], // 'L'
-        [0x63,0x77,0x7F,0x7F,0x6B,0x63,0x63,0x00], // 'M'
-        [0x63,0x67,0x6F,0x7B,0x73,0x63,0x63,0x00], // 'N'
-        [0x3E,0x63,0x63,0x63,0x63,0x63,0x3E,0x00], // 'O'
-        [0x3F,0x66,0x66,0x3E,0x06,0x06,0x0F,0x00], // 'P'
-        [0x3E,0x63,0x63,0x63,0x6B,0x33,0x5E,0x00], // 'Q'
-        [0x3F,0x66,0x66,0x3E,0x36,0x66,0x67,0x00], // 'R'
-        [0x1E,0x33,0x03,0x1E,0x30,0x33,0x1E,0x00], // 'S'
-        [0x3F,0x2D,0x0C,0x0C,0x0C,0x0C,0x1E,0x00], // 'T'
-        [0x33,0x33,0x33,0x33,0x33,0x33,0x3E,0x00], // 'U'
-        [0x33,0x33,0x33,0x33,0x33,0x1E,0x0C,0x00], // 'V'
-        [0x63,0x63,0x63,0x6B,0x7F,0x77,0x63,0x00], // 'W'
-        [0x63,0x63,0x36,0x1C,0x1C,0x36,0x63,0x00], // 'X'
-        [0x33,0x33,0x33,0x1E,0x0C,0x0C,0x1E,0x00], // 'Y'
-        [0x7F,0x63,0x31,0x18,0x4C,0x66,0x7F,0x00], // 'Z'
-        [0x1E,0x06,0x06,0x06,0x06,0x06,0x1E,0x00], // '['
-        [0x03,0x06,0x0C,0x18,0x30,0x60,0x40,0x00], // '\'
-        [0x1E,0x18,0x18,0x18,0x18,0x18,0x1E,0x00], // ']'
-        [0x08,0x1C,0x36,0x63,0x00,0x00,0x00,0x00], // '^'
-        [0x00,0x00,0x00,0x00,0x00,0x00,0x00,0xFF], // '_'
-        [0x0C,0x0C,0x18,0x00,0x00,0x00,0x00,0x00], // '`'
-        [0x00,0x00,0x1E,0x30,0x3E,0x33,0x6E,0x00], // 'a'
-        [0x07,0x06,0x06,0x3E,0x66,0x66,0x3B,0x00], // 'b'
-        [0x00,0x00,0x1E,0x33,0x03,0x33,0x1E,0x00], // 'c'
-        [0x38,0x30,0x30,0x3E,0x33,0x33,0x6E,0x00], // 'd'
-        [0x00,0x00,0x1E,0x33,0x3F,0x03,0x1E,0x00], // 'e'
-        [0x1C,0x36,0x06,0x0F,0x06,0x06,0x0F,0x00], // 'f'
-        [0x00,0x00,0x6E,0x33,0x33,0x3E,0x30,0x1F], // 'g'
-        [0x07,0x06,0x36,0x6E,0x66,0x66,0x67,0x00], // 'h'
-        [0x0C,0x00,0x0E,0x0C,0x0C,0x0C,0x1E,0x00], // 'i'
-        [0x30,0x00,0x38,0x30,0x30,0x33,0x33,0x1E], // 'j'
-        [0x07,0x06,0x66,0x36,0x1E,0x36,0x67,0x00], // 'k'
-        [0x0E,0x0C,0x0C,0x0C,0x0C,0x0C,0x1E,0x00], // 'l'
-        [0x00,0x00,0x33,0x7F,0x7F,0x6B,0x63,0x00], // 'm'
-        [0x00,0x00,0x1F,0x33,0x33,0x33,0x33,0x00], // 'n'
-        [0x00,0x00,0x1E,0x33,0x33,0x33,0x1E,0x00], // 'o'
-        [0x00,0x00,0x3B,0x66,0x66,0x3E,0x06,0x0F], // 'p'
-        [0x00,0x00,0x6E,0x33,0x33,0x3E,0x30,0x78], // 'q'
-        [0x00,0x00,0x3B,0x6E,0x66,0x06,0x0F,0x00], // 'r'
-        [0x00,0x00,0x3E,0x03,0x1E,0x30,0x1F,0x00], // 's'
-        [0x08,0x0C,0x3E,0x0C,0x0C,0x2C,0x18,0x00], // 't'
-        [0x00,0x00,0x33,0x33,0x33,0x33,0x6E,0x00], // 'u'
-        [0x00,0x00,0x33,0x33,0x33,0x1E,0x0C,0x00], // 'v'
-        [0x00,0x00,0x63,0x6B,0x7F,0x7F,0x36,0x00], // 'w'
-        [0x00,0x00,0x63,0x36,0x1C,0x36,0x63,0x00], // 'x'
-        [0x00,0x00,0x33,0x33,0x33,0x3E,0x30,0x1F], // 'y'
-        [0x00,0x00,0x3F,0x19,0x0C,0x26,0x3F,0x00], // 'z'
-        [0x38,0x0C,0x0C,0x07,0x0C,0x0C,0x38,0x00], // '{'
-        [0x0C,0x0C,0x0C,0x00,0x0C,0x0C,0x0C,0x00], // '|'
-        [0x07,0x0C,0x0C,0x38,0x0C,0x0C,0x07,0x00], // '}'
-        [0x6E,0x3B,0x00,0x00,0x00,0x00,0x00,0x00], // '~'
-    ];
-
-    if c < ' ' || c > '~' {
+    // Carga el modelo de esfera
+    let obj = Obj::load("../assets/models/sphere.obj").expect("Failed to load sphere.obj");
+    let meshes = Meshes {
+        sphere: obj.get_vertex_array(),
+    };
+    let mut scene = match options.seed {
+        Some(seed) => {
+            // A stderr: en modo sin ventana stdout puede estar transportando el video
+            eprintln!("🎲 Sistema generado con la semilla {}", seed);
+            Scene::from_seed(seed)
+        }
+        None => Scene::new(),
+    };
+    scene.star = options.star;
+
+    // Benchmark: mide el tiempo por frame sin y con la prueba de profundidad temprana
+    if let Some(frames) = options.bench_frames {
+        bench::run(&mut framebuffer, &mut output, &meshes, &render_settings, options.star, options.seed, frames);
         return;
     }
-    let index = c as usize - 32;
-    let bitmap = FONT[index];
-    for (row, bits) in bitmap.iter().enumerate() {
-        for col in 0..8 {
-            if bits & (1 << col) != 0 {
-                let px = x + col;
-                let py = y + row;
-                if px < framebuffer.width && py < framebuffer.height {
-                    framebuffer.set_current_color(color);
-                    framebuffer.point(px, py, 0.0);
-                }
-            }
+
+    // Modo sin ventana: renderiza N frames y los guarda a disco
+    if let Some(frames) = options.headless_frames {
+        if let Err(err) = headless::run(&mut framebuffer, &mut output, &mut scene, &meshes, &render_settings, frames, &options.recording) {
+            eprintln!("❌ Error exportando frames: {}", err);
+            std::process::exit(1);
         }
+        return;
     }
-}
 
-fn main() {
-    let window_width = 1200;
-    let window_height = 800;
-    let framebuffer_width = 1200;
-    let framebuffer_height = 800;
-    let frame_delay = Duration::from_millis(16);
-
-    let mut framebuffer = Framebuffer::new(framebuffer_width, framebuffer_height);
     let mut window = Window::new(
         "Sistema Solar - Órbitas Planetarias",
-        window_width,
-        window_height,
-        WindowOptions::default(),
+        output.width,
+        output.height,
+        WindowOptions {
+            resize: true,
+            // Mientras se redimensiona, el último frame se estira hasta tener uno nuevo
+            scale_mode: ScaleMode::Stretch,
+            ..WindowOptions::default()
+        },
     )
     .unwrap();
 
     window.set_position(150, 50);
     window.update();
 
-    framebuffer.set_background_color(0x000008); // Negro espacio profundo
-
-    // Carga el modelo de esfera
-    let obj = Obj::load("../assets/models/sphere.obj").expect("Failed to load sphere.obj");
-    let vertex_arrays = obj.get_vertex_array();
-
-    let mut time = 0.0f32;
-
-    // Centro del sistema (donde está el sol)
-    let sun_center_x = 600.0;
-    let sun_center_y = 450.0;
-
-    // Configuración de la luna del planeta rocoso
-    let mut moon_angle: f32 = 0.0;
-    let moon_orbital_radius = 100.0; // distancia desde el planeta
-    let moon_speed = 0.05;
-    let moon_scale = 40.0;
-
-    // Define los cuerpos celestes con órbitas
-let mut bodies = vec![
-    CelestialBody {
-        orbital_radius: 250.0,
-        orbital_angle: 0.0,
-        orbital_speed: 0.02,
-        scale: 80.0,
-        visible_radius: 80.0 / 2.0, // planeta rocoso sin anillos
-        rotation: Vec3::new(0.0, 0.0, 0.0),
-        shader_type: ShaderType::RockyPlanet,
-        rotation_speed: 0.015,
-        name: "Planeta Rocoso",
-    },
-    CelestialBody {
-        orbital_radius: 480.0,
-        orbital_angle: std::f32::consts::PI,
-        orbital_speed: 0.01,
-        scale: 130.0,
-        visible_radius: 130.0 / 2.0 + 80.0, // 👈 planeta gaseoso + anillos
-        rotation: Vec3::new(0.0, 0.0, 0.0),
-        shader_type: ShaderType::GasGiant,
-        rotation_speed: 0.012,
-        name: "Gigante Gaseoso",
-    },
-    CelestialBody {
-        orbital_radius: 620.0,
-        orbital_angle: std::f32::consts::PI * 1.5,
-        orbital_speed: 0.007,
-        scale: 100.0,
-        visible_radius: 100.0 / 2.0,
-        rotation: Vec3::new(0.0, 0.0, 0.0),
-        shader_type: ShaderType::IcePlanet,
-        rotation_speed: 0.01,
-        name: "Planeta Helado",
-    },
-];
-
     // Camera control
-    let mut camera_zoom = 1.0f32;
-    let mut camera_x = 0.0f32;
-    let mut camera_y = 0.0f32;
     let camera_speed = 10.0;
 
-    let mut paused = false;
     let mut show_orbits = true;
 
+    // Grabación activa (tecla G)
+    let mut recorder: Option<Recorder> = None;
+    let mut recording_count = 0;
+
     println!("╔════════════════════════════════════════════════════════╗");
     println!("║        SISTEMA SOLAR - ÓRBITAS PLANETARIAS            ║");
     println!("╚════════════════════════════════════════════════════════╝");
     println!();
-    println!("☀️  SOL (Centro) con {} planetas en órbita", bodies.len());
+    println!("☀️  SOL (Centro) con {} planetas en órbita", scene.bodies.len());
     println!();
     println!("🪐 PLANETAS (desde el más cercano):");
     println!("  1. 🌙 Luna - Órbita: 180 (muy rápida)");
@@ -398,25 +778,31 @@ let mut bodies = vec![
     println!("  ESPACIO : Pausar/Reanudar órbitas");
     println!("  O : Mostrar/Ocultar órbitas (WIP)");
     println!("  R : Reset cámara al centro");
+    println!("  G : Iniciar/Detener grabación");
+    println!("  T : Cambiar tone mapping (clamp/reinhard/aces)");
+    println!("  - / = : Bajar/Subir exposición");
+    println!("  B : Activar/Desactivar bloom");
+    println!("  K : Cambiar tipo de estrella (sol/enana roja/enana blanca/gigante azul)");
+    println!("  P : Generar un sistema nuevo (imprime su semilla)");
+    println!("  Z / N / V : Vista de profundidad / normales / overdraw");
+    println!("  W : Mostrar/Ocultar wireframe");
+    println!("  D : Activar/Desactivar dithering");
+    println!("  I : Interpolación con corrección de perspectiva / afín");
+    println!("  H : Mostrar/Ocultar ayuda");
+    println!("  [ / ] : Bajar/Subir escala de render");
     println!("  ESC : Salir");
     println!();
     println!("💡 TIP: ¡Observa cómo los planetas internos orbitan más rápido!");
+    println!("💡 TIP: Usa --threads <N> para elegir los hilos de rasterización (1 = serial)");
+    println!("💡 TIP: Usa --scale <porcentaje> (por ejemplo 50) para renderizar más rápido en equipos lentos");
+    println!("💡 TIP: Usa --star red-dwarf|white-dwarf|blue-giant o --star-temperature <K> para cambiar la estrella");
+    println!("💡 TIP: Usa --fov <grados> (por ejemplo 60) para ver la escena con perspectiva");
+    println!("💡 TIP: Usa --seed <N> para repetir un sistema generado con P");
+    println!("💡 TIP: Usa --bench <N> para comparar el tiempo por frame sin y con la prueba de profundidad temprana");
+    println!("💡 TIP: Usa --samples 4 (o 9, 16) para suavizar los bordes con supersampling");
+    println!("💡 TIP: Usa --headless <N> [--out <dir>] [--format png|ppm|gif|y4m] [--every <N>] para exportar frames sin ventana");
     println!();
 
-    // Evitar colisiones ajustando las distancias orbitales
-for i in 1..bodies.len() {
-    let prev = &bodies[i - 1];
-    let min_distance = prev.orbital_radius + prev.visible_radius + bodies[i].visible_radius + 40.0; // 40px de margen
-    if bodies[i].orbital_radius < min_distance {
-        bodies[i].orbital_radius = min_distance;
-    }
-}
-
-let moon_orbital_radius = 100.0;
-if moon_orbital_radius < bodies[0].visible_radius + moon_scale {
-    println!("⚠️ Ajustando órbita lunar para evitar colisión con el planeta rocoso");
-}
-
     while window.is_open() {
         if window.is_key_down(Key::Escape) {
             break;
@@ -424,8 +810,8 @@ if moon_orbital_radius < bodies[0].visible_radius + moon_scale {
 
         // Toggle pause
         if window.is_key_pressed(Key::Space, minifb::KeyRepeat::No) {
-            paused = !paused;
-            println!("{}", if paused { "⏸️  Sistema PAUSADO" } else { "▶️  Sistema en MOVIMIENTO" });
+            scene.paused = !scene.paused;
+            println!("{}", if scene.paused { "⏸️  Sistema PAUSADO" } else { "▶️  Sistema en MOVIMIENTO" });
         }
 
         // Toggle orbits
@@ -436,179 +822,181 @@ if moon_orbital_radius < bodies[0].visible_radius + moon_scale {
 
         // Reset camera
         if window.is_key_pressed(Key::R, minifb::KeyRepeat::No) {
-            camera_x = 0.0;
-            camera_y = 0.0;
-            camera_zoom = 1.0;
+            scene.reset_camera();
             println!("📷 Cámara reseteada");
         }
 
+        // Cycle tone mapping
+        if window.is_key_pressed(Key::T, minifb::KeyRepeat::No) {
+            let tone_mapping = framebuffer.tone_mapping().next();
+            framebuffer.set_tone_mapping(tone_mapping);
+            println!("🎚️  Tone mapping: {}", tone_mapping.name());
+        }
+
+        // Exposure
+        if window.is_key_pressed(Key::Minus, minifb::KeyRepeat::Yes) {
+            framebuffer.set_exposure(framebuffer.exposure() * 0.9);
+            println!("🔆 Exposición: {:.2}", framebuffer.exposure());
+        }
+        if window.is_key_pressed(Key::Equal, minifb::KeyRepeat::Yes) {
+            framebuffer.set_exposure(framebuffer.exposure() * 1.1);
+            println!("🔆 Exposición: {:.2}", framebuffer.exposure());
+        }
+
+        // Toggle bloom
+        if window.is_key_pressed(Key::B, minifb::KeyRepeat::No) {
+            framebuffer.bloom.enabled = !framebuffer.bloom.enabled;
+            println!("✨ Bloom: {}", if framebuffer.bloom.enabled { "Activado" } else { "Desactivado" });
+        }
+
+        // Tipo de estrella: sol, enana roja, enana blanca, gigante azul
+        if window.is_key_pressed(Key::K, minifb::KeyRepeat::No) {
+            scene.star = scene.star.next();
+            println!("⭐ Estrella: {} ({:.0} K)", scene.star.name, scene.star.temperature);
+        }
+
+        // Sistema procedural: la semilla se imprime para poder repetirlo con --seed
+        if window.is_key_pressed(Key::P, minifb::KeyRepeat::No) {
+            let seed = generator::random_seed();
+            scene.regenerate(seed);
+            println!("🎲 Sistema generado con la semilla {} ({} planetas)", seed, scene.bodies.len());
+        }
+
+        // Toggle HUD
+        if window.is_key_pressed(Key::H, minifb::KeyRepeat::No) {
+            render_settings.show_hud = !render_settings.show_hud;
+            println!("Ayuda: {}", if render_settings.show_hud { "Visible" } else { "Oculta" });
+        }
+
+        // Vistas de depuración: Z profundidad, N normales, V overdraw, W wireframe
+        let debug_keys = [(Key::Z, DebugView::Depth), (Key::N, DebugView::Normals), (Key::V, DebugView::Overdraw)];
+        for (key, view) in debug_keys {
+            if window.is_key_pressed(key, minifb::KeyRepeat::No) {
+                framebuffer.debug_view = framebuffer.debug_view.toggle(view);
+                println!("🔍 Vista: {}", framebuffer.debug_view.name());
+            }
+        }
+        if window.is_key_pressed(Key::W, minifb::KeyRepeat::No) {
+            framebuffer.wireframe = !framebuffer.wireframe;
+            println!("🔍 Wireframe: {}", if framebuffer.wireframe { "Visible" } else { "Oculto" });
+        }
+
+        // Dithering: compara el degradado con y sin bandas
+        if window.is_key_pressed(Key::D, minifb::KeyRepeat::No) {
+            framebuffer.dither = framebuffer.dither.toggle();
+            println!("🎚️  Dithering: {}", framebuffer.dither.name());
+        }
+
+        // Interpolación de atributos: con corrección de perspectiva o afín
+        if window.is_key_pressed(Key::I, minifb::KeyRepeat::No) {
+            render_settings.interpolation = render_settings.interpolation.toggle();
+            println!("📐 Interpolación: {}", render_settings.interpolation.name());
+        }
+
+        // Toggle recording
+        if window.is_key_pressed(Key::G, minifb::KeyRepeat::No) {
+            match recorder.take() {
+                Some(active) => finish_recording(active),
+                None => {
+                    recording_count += 1;
+                    let session = format!("recording_{:03}", recording_count);
+                    match Recorder::start(&options.recording, Some(&session), output.width, output.height) {
+                        Ok(started) => {
+                            println!("🔴 Grabando en {}", started.path().display());
+                            recorder = Some(started);
+                        }
+                        Err(err) => eprintln!("❌ No se pudo iniciar la grabación: {}", err),
+                    }
+                }
+            }
+        }
+
         // Camera movement
         if window.is_key_down(Key::Right) {
-            camera_x -= camera_speed;
+            scene.camera_x -= camera_speed;
         }
         if window.is_key_down(Key::Left) {
-            camera_x += camera_speed;
+            scene.camera_x += camera_speed;
         }
         if window.is_key_down(Key::Up) {
-            camera_y += camera_speed;
+            scene.camera_y += camera_speed;
         }
         if window.is_key_down(Key::Down) {
-            camera_y -= camera_speed;
+            scene.camera_y -= camera_speed;
         }
 
         // Zoom
         if window.is_key_down(Key::S) {
-            camera_zoom += 0.01;
-            if camera_zoom > 2.0 {
-                camera_zoom = 2.0;
+            scene.camera_zoom += 0.01;
+            if scene.camera_zoom > 2.0 {
+                scene.camera_zoom = 2.0;
             }
         }
         if window.is_key_down(Key::A) {
-            camera_zoom -= 0.01;
-            if camera_zoom < 0.3 {
-                camera_zoom = 0.3;
+            scene.camera_zoom -= 0.01;
+            if scene.camera_zoom < MIN_ZOOM {
+                scene.camera_zoom = MIN_ZOOM;
             }
         }
 
-        framebuffer.clear();
-
-        // Update time
-        if !paused {
-            time += 0.016;
-            
-            // Update orbital positions and rotations
-            for body in &mut bodies {
-                body.orbital_angle += body.orbital_speed;
-                body.rotation.y += body.rotation_speed;
+        // Escala de render interna
+        let render_scale_keys = [(Key::LeftBracket, false), (Key::RightBracket, true)];
+        for (key, up) in render_scale_keys {
+            if window.is_key_pressed(key, minifb::KeyRepeat::No) {
+                render_settings.render_scale = step_render_scale(render_settings.render_scale, up);
+                fit_render_target(&mut framebuffer, &render_settings, output.width, output.height);
+                println!(
+                    "🖥️  Escala de render: {:.0}% ({}x{})",
+                    render_settings.render_scale * 100.0,
+                    framebuffer.width,
+                    framebuffer.height
+                );
             }
         }
 
-        // Calculate sun position with camera
-        let sun_screen_x = sun_center_x * camera_zoom + camera_x;
-        let sun_screen_y = sun_center_y * camera_zoom + camera_y;
-
-        // Render the SUN first (always at center)
-        let sun_position = Vec3::new(sun_screen_x, sun_screen_y, 0.0);
-        let sun_matrix = create_model_matrix(
-            sun_position,
-            140.0 * camera_zoom,
-            Vec3::new(0.0, time * 0.005, 0.0)
-        );
-        let sun_uniforms = Uniforms { model_matrix: sun_matrix };
-        render(&mut framebuffer, &sun_uniforms, &vertex_arrays, ShaderType::Sun, time);
-
-        // Render all planets in their orbits
-        for body in &bodies {
-            // Calculate orbital position using polar coordinates
-            let orbit_x = sun_center_x + body.orbital_radius * body.orbital_angle.cos();
-            let orbit_y = sun_center_y + body.orbital_radius * body.orbital_angle.sin();
-            
-            // Apply camera transformations
-            let screen_position = Vec3::new(
-                orbit_x * camera_zoom + camera_x,
-                orbit_y * camera_zoom + camera_y,
-                0.0
-            );
-
-            // Only render if visible on screen (with generous margin)
-            if screen_position.x > -300.0 && screen_position.x < window_width as f32 + 300.0 &&
-               screen_position.y > -300.0 && screen_position.y < window_height as f32 + 300.0 {
-                
-                let model_matrix = create_model_matrix(
-                    screen_position,
-                    body.scale * camera_zoom,
-                    body.rotation
-                );
-                let uniforms = Uniforms { model_matrix };
-
-                render(&mut framebuffer, &uniforms, &vertex_arrays, body.shader_type, time);
+        // Ventana redimensionada: ajusta la salida y el framebuffer de render
+        let (window_width, window_height) = window.get_size();
+        if window_width > 0 && window_height > 0 && (window_width, window_height) != (output.width, output.height) {
+            output.resize(window_width, window_height);
+            fit_render_target(&mut framebuffer, &render_settings, window_width, window_height);
+            // Los GIF y videos tienen un tamaño fijo
+            if let Some(active) = recorder.take() {
+                println!("⚠️  La ventana cambió de tamaño: se detiene la grabación");
+                finish_recording(active);
             }
         }
 
-        // Mostrar instrucciones en esquina inferior izquierda
-        let instructions_y = framebuffer_height - 90;
-        let color_text = 0xFFFFFF;
-
-        draw_text(
-            &mut framebuffer,
-            20,
-            instructions_y,
-            "Presiona ESPACIO para pausar cuando los planetas estén en buenas posiciones",
-            color_text,
-        );
-        draw_text(
-            &mut framebuffer,
-            20,
-            instructions_y + 15,
-            "* Usa A/S para hacer zoom y capturar detalles",
-            color_text,
-        );
-        draw_text(
-            &mut framebuffer,
-            20,
-            instructions_y + 30,
-            "* Usa flechas para centrarte en cada planeta",
-            color_text,
-        );
-        draw_text(
-            &mut framebuffer,
-            20,
-            instructions_y + 45,
-            "* Presiona R para volver al centro y ver todo el sistema",
-            color_text,
-        );
-
-        // Dibuja los anillos del planeta gaseoso
-let gas_giant = &bodies[2]; // el gigante gaseoso en tu lista
-let gas_x = sun_center_x + gas_giant.orbital_radius * gas_giant.orbital_angle.cos();
-let gas_y = sun_center_y + gas_giant.orbital_radius * gas_giant.orbital_angle.sin();
-
-let gas_screen = Vec3::new(
-    gas_x * camera_zoom + camera_x,
-    gas_y * camera_zoom + camera_y,
-    0.0,
-);
-
-
-draw_ring(
-    &mut framebuffer,
-    gas_screen,
-    160.0 * camera_zoom, // radio interno
-    220.0 * camera_zoom, // radio externo
-    0xAAAAAA,            // color gris claro
-);
-
-// Dibuja la luna orbitando el planeta rocoso
-let rocky_planet = &bodies[0]; // el primero en tu lista
-let rocky_x = sun_center_x + rocky_planet.orbital_radius * rocky_planet.orbital_angle.cos();
-let rocky_y = sun_center_y + rocky_planet.orbital_radius * rocky_planet.orbital_angle.sin();
-
-if !paused {
-    moon_angle += moon_speed;
-}
-
-let moon_x = rocky_x + moon_orbital_radius * moon_angle.cos();
-let moon_y = rocky_y + moon_orbital_radius * moon_angle.sin();
+        framebuffer.clear();
 
-let moon_screen = Vec3::new(
-    moon_x * camera_zoom + camera_x,
-    moon_y * camera_zoom + camera_y,
-    0.0,
-);
+        // Update time
+        scene.update();
 
-let moon_matrix = create_model_matrix(
-    moon_screen,
-    moon_scale * camera_zoom,
-    Vec3::new(0.0, moon_angle * 2.0, 0.0),
-);
-let moon_uniforms = Uniforms { model_matrix: moon_matrix };
-render(&mut framebuffer, &moon_uniforms, &vertex_arrays, ShaderType::Moon, time);
+        render_frame(&mut framebuffer, &mut output, &scene, &meshes, &render_settings);
 
+        if let Some(active) = recorder.as_mut()
+            && let Err(err) = active.capture(&output)
+        {
+            eprintln!("❌ Error grabando frame: {}", err);
+            recorder = None;
+        }
 
         window
-            .update_with_buffer(&framebuffer.buffer, framebuffer_width, framebuffer_height)
+            .update_with_buffer(&output.buffer, output.width, output.height)
             .unwrap();
 
         std::thread::sleep(frame_delay);
     }
+
+    if let Some(active) = recorder.take() {
+        finish_recording(active);
+    }
+}
+
+fn finish_recording(recorder: Recorder) {
+    let path = recorder.path().to_path_buf();
+    let captured = recorder.captured();
+    match recorder.finish() {
+        Ok(()) => println!("⏹️  Grabación guardada en {} ({} frames)", path.display(), captured),
+        Err(err) => eprintln!("❌ Error cerrando la grabación: {}", err),
+    }
 }
\ No newline at end of file
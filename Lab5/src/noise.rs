@@ -0,0 +1,244 @@
+// noise.rs
+// Ruido simplex en 3D y 4D con un hash entero. Los planetas lo muestrean directamente sobre
+// la posición en la esfera unitaria: sin la costura donde `atan2` da la vuelta ni los polos
+// pellizcados que deja mapear ruido 2D sobre (theta, phi). La cuarta dimensión es el tiempo,
+// para que un patrón (nubes, lava) cambie en su lugar en vez de deslizarse
+
+use std::array::from_fn;
+
+use nalgebra_glm::Vec3;
+
+// Escalas para que `noise3`/`noise4` tengan la misma dispersión (desviación ~0.21 alrededor
+// de 0.5) que el ruido de valor anterior, así los umbrales de los shaders siguen valiendo
+const SCALE_3D: f32 = 42.0;
+const SCALE_4D: f32 = 53.0;
+
+/// Ruido en [0, 1] en el punto `p`
+pub fn noise3(p: &Vec3) -> f32 {
+    (0.5 + simplex([p.x, p.y, p.z], gradient3) * SCALE_3D).clamp(0.0, 1.0)
+}
+
+/// Ruido en [0, 1] en el punto `p` en el instante `w`
+pub fn noise4(p: &Vec3, w: f32) -> f32 {
+    (0.5 + simplex([p.x, p.y, p.z, w], gradient4) * SCALE_4D).clamp(0.0, 1.0)
+}
+
+/// Desplazamiento del dominio del ruido para la semilla `seed`. Cada eje toma 16 bits del
+/// hash de la semilla completa, en [0, 1024) con pasos de 1/64: acotado para que `f32`
+/// conserve la precisión del ruido en las octavas finas. La semilla 0 no desplaza
+pub fn seed_offset(seed: u32) -> Vec3 {
+    if seed == 0 {
+        return Vec3::zeros();
+    }
+    let axis = |a: i32| (hash([seed as i32, a]) & 0xffff) as f32 / 64.0;
+    Vec3::new(axis(0), axis(1), axis(2))
+}
+
+/// Suma `octaves` capas de `noise3`, cada una al doble de frecuencia y la mitad de amplitud
+pub fn fbm(p: &Vec3, octaves: u32) -> f32 {
+    let mut value = 0.0;
+    let mut amplitude = 0.5;
+    let mut frequency = 1.0;
+
+    for _ in 0..octaves {
+        value += amplitude * noise3(&(p * frequency));
+        amplitude *= 0.5;
+        frequency *= 2.0;
+    }
+
+    value
+}
+
+/// Como `fbm`, animado: el tiempo `w` avanza más rápido en las octavas finas
+pub fn fbm4(p: &Vec3, w: f32, octaves: u32) -> f32 {
+    let mut value = 0.0;
+    let mut amplitude = 0.5;
+    let mut frequency = 1.0;
+
+    for _ in 0..octaves {
+        value += amplitude * noise4(&(p * frequency), w * frequency);
+        amplitude *= 0.5;
+        frequency *= 2.0;
+    }
+
+    value
+}
+
+// Ruido simplex en N dimensiones. El espacio se divide en símplices (triángulos en 2D,
+// tetraedros en 3D); cada punto suma la contribución de las N + 1 esquinas del suyo, cada una
+// un gradiente pseudoaleatorio que se atenúa con la distancia. El resultado está centrado en 0
+fn simplex<const N: usize>(point: [f32; N], gradient: fn(u32, &[f32; N]) -> f32) -> f32 {
+    let n = N as f32;
+    let skew = ((n + 1.0).sqrt() - 1.0) / n;
+    let unskew = (1.0 - 1.0 / (n + 1.0).sqrt()) / n;
+
+    // Celda del retículo sesgado que contiene el punto y posición dentro de ella
+    let skewed = point.iter().sum::<f32>() * skew;
+    let cell: [i32; N] = point.map(|c| (c + skewed).floor() as i32);
+    let unskewed = cell.iter().sum::<i32>() as f32 * unskew;
+    let offset: [f32; N] = from_fn(|d| point[d] - (cell[d] as f32 - unskewed));
+
+    // El símplex va de la esquina de la celda a la opuesta sumando 1 a cada eje, del de
+    // mayor desplazamiento al de menor: `rank` es cuántos ejes tienen menos desplazamiento
+    let mut rank = [0usize; N];
+    for a in 0..N {
+        for b in a + 1..N {
+            if offset[a] > offset[b] {
+                rank[a] += 1;
+            } else {
+                rank[b] += 1;
+            }
+        }
+    }
+
+    let mut total = 0.0;
+    for step in 0..=N {
+        let shift = step as f32 * unskew;
+        let mut corner = [0i32; N];
+        let mut local = [0.0; N];
+        let mut falloff = 0.5;
+        for d in 0..N {
+            corner[d] = (rank[d] + step >= N) as i32;
+            local[d] = offset[d] - corner[d] as f32 + shift;
+            falloff -= local[d] * local[d];
+        }
+        if falloff > 0.0 {
+            let hash = hash::<N>(from_fn(|d| cell[d] + corner[d]));
+            let falloff2 = falloff * falloff;
+            total += falloff2 * falloff2 * gradient(hash, &local);
+        }
+    }
+    total
+}
+
+// Hash entero de un punto del retículo: mezcla las coordenadas con constantes impares y
+// las avalancha con el finalizador de MurmurHash3. A diferencia de `sin()`, es exacto para
+// coordenadas grandes y da lo mismo en cualquier plataforma
+fn hash<const N: usize>(cell: [i32; N]) -> u32 {
+    const PRIMES: [u32; 4] = [0x8da6_b343, 0xd816_3841, 0xcb1a_b31f, 0x1656_67b1];
+    let mut h = cell.iter().zip(PRIMES).fold(0u32, |h, (&c, prime)| h ^ (c as u32).wrapping_mul(prime));
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x85eb_ca6b);
+    h ^= h >> 13;
+    h = h.wrapping_mul(0xc2b2_ae35);
+    h ^ (h >> 16)
+}
+
+// Gradientes 3D: los 12 puntos medios de las aristas de un cubo
+fn gradient3(hash: u32, p: &[f32; 3]) -> f32 {
+    const GRADIENTS: [[f32; 3]; 12] = [
+        [1.0, 1.0, 0.0], [-1.0, 1.0, 0.0], [1.0, -1.0, 0.0], [-1.0, -1.0, 0.0],
+        [1.0, 0.0, 1.0], [-1.0, 0.0, 1.0], [1.0, 0.0, -1.0], [-1.0, 0.0, -1.0],
+        [0.0, 1.0, 1.0], [0.0, -1.0, 1.0], [0.0, 1.0, -1.0], [0.0, -1.0, -1.0],
+    ];
+    let g = GRADIENTS[(hash % 12) as usize];
+    g[0] * p[0] + g[1] * p[1] + g[2] * p[2]
+}
+
+// Gradientes 4D: los 32 puntos medios de las aristas de un teseracto (un eje en 0 y los
+// otros tres en ±1). Los dos bits bajos eligen el eje nulo y los tres siguientes los signos
+fn gradient4(hash: u32, p: &[f32; 4]) -> f32 {
+    let zero_axis = (hash & 3) as usize;
+    let mut sign_bit = 2;
+    let mut total = 0.0;
+    for (axis, &component) in p.iter().enumerate() {
+        if axis == zero_axis {
+            continue;
+        }
+        total += if hash >> sign_bit & 1 == 0 { component } else { -component };
+        sign_bit += 1;
+    }
+    total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::PI;
+
+    // Puntos repartidos en un cubo amplio, con coordenadas negativas y fuera de la esfera
+    fn sample_points() -> impl Iterator<Item = Vec3> {
+        (0..4000).map(|i| {
+            let t = i as f32;
+            Vec3::new((t * 0.37).sin() * 50.0, (t * 0.11).cos() * 30.0 - t * 0.01, t * 0.023 - 40.0)
+        })
+    }
+
+    #[test]
+    fn noise_stays_in_unit_range() {
+        for p in sample_points() {
+            let value = noise3(&p);
+            assert!((0.0..=1.0).contains(&value), "noise3({p:?}) = {value}");
+            let value = noise4(&p, p.x * 0.5 - p.z);
+            assert!((0.0..=1.0).contains(&value), "noise4({p:?}) = {value}");
+        }
+    }
+
+    #[test]
+    fn noise_matches_golden_values() {
+        // Valores fijos: el hash entero y la aritmética de `f32` dan lo mismo en cualquier
+        // plataforma, así un cambio en el ruido (y en el aspecto de los planetas) se nota aquí
+        let golden = [
+            (Vec3::new(0.3, -1.7, 2.25), [0.808_720_8, 0.639_762, 0.793_104_8, 0.460_275_4]),
+            (Vec3::new(12.34, 5.67, -8.9), [0.661_992_7, 0.495_734_6, 0.541_397_7, 0.444_139_7]),
+            (Vec3::new(-0.61, 0.42, 0.67), [0.873_249_1, 0.631_495_8, 0.702_874_2, 0.479_309_3]),
+            (Vec3::new(101.5, -33.25, 7.125), [0.274_762_2, 0.439_644_3, 0.402_732_9, 0.366_622_7]),
+        ];
+        for (p, expected) in golden {
+            let values = [noise3(&p), noise4(&p, 0.77), fbm(&p, 5), fbm4(&p, 0.77, 3)];
+            for (value, expected) in values.iter().zip(expected) {
+                assert!((value - expected).abs() < 1e-6, "{p:?}: {values:?} != {expected}");
+            }
+        }
+        // En los vértices del retículo todas las contribuciones se anulan
+        assert_eq!(noise3(&Vec3::zeros()), 0.5);
+        assert_eq!(noise4(&Vec3::zeros(), 0.0), 0.5);
+    }
+
+    #[test]
+    fn sphere_noise_has_no_seam_or_pinched_pole() {
+        // Punto de la esfera unitaria; `longitude` da la vuelta en ±PI como `atan2`
+        let sphere = |longitude: f32, latitude: f32| {
+            Vec3::new(latitude.cos() * longitude.cos(), latitude.cos() * longitude.sin(), latitude.sin())
+        };
+        let sample = |p: Vec3| fbm(&(p * 3.0), 5);
+
+        // A ambos lados de la costura el ruido es el mismo, a cualquier latitud
+        for i in 0..20 {
+            let latitude = -1.4 + i as f32 * 0.14;
+            let west = sample(sphere(PI - 1e-4, latitude));
+            let east = sample(sphere(-PI + 1e-4, latitude));
+            assert!((west - east).abs() < 1e-2, "costura en la latitud {latitude}: {west} vs {east}");
+        }
+
+        // Un anillo diminuto alrededor del polo toma el valor del polo en todas las longitudes,
+        // en vez de estirar el patrón hacia el polo
+        let pole = sample(Vec3::new(0.0, 0.0, 1.0));
+        for i in 0..36 {
+            let value = sample(sphere(i as f32 * PI / 18.0, PI / 2.0 - 1e-4));
+            assert!((value - pole).abs() < 1e-2, "polo: {value} vs {pole}");
+        }
+    }
+
+    #[test]
+    fn noise_varies() {
+        let values: Vec<f32> = sample_points().map(|p| noise3(&p)).collect();
+        let mean = values.iter().sum::<f32>() / values.len() as f32;
+        assert!((mean - 0.5).abs() < 0.05, "media {mean}");
+        assert!(values.iter().any(|&v| v < 0.3) && values.iter().any(|&v| v > 0.7));
+    }
+
+    #[test]
+    fn seed_offset_is_bounded_and_distinct() {
+        assert_eq!(seed_offset(0), Vec3::zeros());
+        let offsets: Vec<Vec3> = (1..=2000).map(seed_offset).collect();
+        for offset in &offsets {
+            assert!(offset.iter().all(|&c| (0.0..1024.0).contains(&c)), "{offset:?}");
+        }
+        // Semillas que antes coincidían módulo 1000 ya no comparten desplazamiento
+        assert_ne!(seed_offset(7), seed_offset(1007));
+        for (i, a) in offsets.iter().enumerate() {
+            assert!(offsets[i + 1..].iter().all(|b| a != b));
+        }
+    }
+}
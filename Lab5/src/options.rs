@@ -0,0 +1,232 @@
+// options.rs
+// Opciones de línea de comandos
+
+use std::path::PathBuf;
+
+use crate::color::{Dither, ToneMapping};
+use crate::debug::DebugView;
+use crate::framebuffer::BlendMode;
+use crate::raster;
+use crate::recorder::{RecordFormat, RecordingSettings};
+use crate::star::{self, Star};
+use crate::triangle::Interpolation;
+
+pub struct Options {
+    pub headless_frames: Option<usize>,
+    pub bench_frames: Option<usize>,
+    pub recording: RecordingSettings,
+    pub tone_mapping: ToneMapping,
+    pub exposure: f32,
+    pub bloom: bool,
+    pub bloom_threshold: f32,
+    pub bloom_strength: f32,
+    pub ring_blend: BlendMode,
+    pub samples: usize,
+    pub threads: usize,
+    pub render_scale: f32,
+    pub debug_view: DebugView,
+    pub wireframe: bool,
+    pub dither: Dither,
+    pub fov: f32,
+    pub interpolation: Interpolation,
+    pub star: Star,
+    pub early_depth_test: bool,
+    pub seed: Option<u64>,
+}
+
+impl Options {
+    /// Lee las opciones de la línea de comandos:
+    /// `--headless <N>`, `--out <dir>`, `--format png|ppm|gif|y4m` y `--every <N>`.
+    /// Con `--format y4m`, `--out -` escribe el video a la salida estándar.
+    /// `--tonemap clamp|reinhard|aces` y `--exposure <f>` controlan la resolución del buffer HDR;
+    /// `--bloom-threshold <f>`, `--bloom-strength <f>` y `--no-bloom` configuran el bloom;
+    /// `--ring-blend alpha|additive|multiply|opaque` elige cómo se mezclan los anillos;
+    /// `--samples 1|4|9|16` activa el antialiasing por supersampling y
+    /// `--threads <N>` elige los hilos de rasterización (1 usa el camino serial).
+    /// `--debug depth|normals|overdraw` y `--wireframe` arrancan con una vista de depuración.
+    /// `--scale <porcentaje>` fija la resolución interna de render (por ejemplo 50 o 200).
+    /// `--dither bayer|none` elige el dithering al cuantizar a 8 bits (`--no-dither` equivale a `none`).
+    /// `--fov <grados>` usa proyección en perspectiva (0 = ortográfica) y `--affine` interpola
+    /// los atributos sin corrección de perspectiva, para comparar.
+    /// `--star sun|red-dwarf|white-dwarf|blue-giant` elige la estrella central y
+    /// `--star-temperature <K>` / `--star-luminosity <f>` ajustan su color y brillo.
+    /// `--no-early-z` sombrea los fragmentos antes de probar la profundidad y
+    /// `--bench <N>` compara el tiempo por frame sin y con la prueba temprana.
+    /// `--seed <N>` reemplaza el sistema por uno generado a partir de esa semilla.
+    pub fn from_args(args: &[String]) -> Result<Self, String> {
+        let mut options = Options {
+            headless_frames: None,
+            bench_frames: None,
+            recording: RecordingSettings::default(),
+            tone_mapping: ToneMapping::Aces,
+            exposure: 1.0,
+            bloom: true,
+            bloom_threshold: 1.0,
+            bloom_strength: 0.6,
+            ring_blend: BlendMode::Alpha,
+            samples: 1,
+            threads: raster::default_threads(),
+            render_scale: 1.0,
+            debug_view: DebugView::Shaded,
+            wireframe: false,
+            dither: Dither::Bayer,
+            fov: 0.0,
+            interpolation: Interpolation::Perspective,
+            star: Star::sun(),
+            early_depth_test: true,
+            seed: None,
+        };
+        // Se aplican al final para que no dependan del orden respecto a `--star`
+        let mut star_temperature = None;
+        let mut star_luminosity = None;
+
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "--headless" => {
+                    let value = iter.next().ok_or("--headless requiere el número de frames")?;
+                    options.headless_frames = Some(parse_count(value)?);
+                }
+                "--out" => {
+                    let value = iter.next().ok_or("--out requiere un directorio")?;
+                    options.recording.output_dir = PathBuf::from(value);
+                }
+                "--format" => {
+                    let value = iter.next().ok_or("--format requiere png, ppm, gif o y4m")?;
+                    options.recording.format = RecordFormat::from_name(value)
+                        .ok_or_else(|| format!("Formato desconocido: {}", value))?;
+                }
+                "--every" => {
+                    let value = iter.next().ok_or("--every requiere un número de frames")?;
+                    options.recording.every = parse_count(value)?.max(1);
+                }
+                "--tonemap" => {
+                    let value = iter.next().ok_or("--tonemap requiere clamp, reinhard o aces")?;
+                    options.tone_mapping = ToneMapping::from_name(value)
+                        .ok_or_else(|| format!("Tone mapping desconocido: {}", value))?;
+                }
+                "--exposure" => {
+                    let value = iter.next().ok_or("--exposure requiere un valor")?;
+                    options.exposure = parse_float(value)?;
+                }
+                "--bloom-threshold" => {
+                    let value = iter.next().ok_or("--bloom-threshold requiere un valor")?;
+                    options.bloom_threshold = parse_float(value)?;
+                }
+                "--bloom-strength" => {
+                    let value = iter.next().ok_or("--bloom-strength requiere un valor")?;
+                    options.bloom_strength = parse_float(value)?;
+                }
+                "--no-bloom" => options.bloom = false,
+                "--ring-blend" => {
+                    let value = iter.next().ok_or("--ring-blend requiere alpha, additive, multiply u opaque")?;
+                    options.ring_blend = BlendMode::from_name(value)
+                        .ok_or_else(|| format!("Modo de mezcla desconocido: {}", value))?;
+                }
+                "--samples" => {
+                    let value = iter.next().ok_or("--samples requiere 1, 4, 9 o 16")?;
+                    let samples = parse_count(value)?;
+                    if !matches!(samples, 1 | 4 | 9 | 16) {
+                        return Err(format!("Cantidad de muestras no soportada: {} (usa 1, 4, 9 o 16)", samples));
+                    }
+                    options.samples = samples;
+                }
+                "--threads" => {
+                    let value = iter.next().ok_or("--threads requiere un número de hilos")?;
+                    options.threads = parse_count(value)?.max(1);
+                }
+                "--scale" => {
+                    let value = iter.next().ok_or("--scale requiere un porcentaje")?;
+                    let percent = parse_float(value)?;
+                    if !(10.0..=400.0).contains(&percent) {
+                        return Err(format!("Escala fuera de rango: {} (usa de 10 a 400)", value));
+                    }
+                    options.render_scale = percent / 100.0;
+                }
+                "--debug" => {
+                    let value = iter.next().ok_or("--debug requiere depth, normals u overdraw")?;
+                    options.debug_view = DebugView::from_name(value)
+                        .ok_or_else(|| format!("Vista de depuración desconocida: {}", value))?;
+                }
+                "--wireframe" => options.wireframe = true,
+                "--dither" => {
+                    let value = iter.next().ok_or("--dither requiere bayer o none")?;
+                    options.dither = Dither::from_name(value)
+                        .ok_or_else(|| format!("Dithering desconocido: {}", value))?;
+                }
+                "--no-dither" => options.dither = Dither::None,
+                "--fov" => {
+                    let value = iter.next().ok_or("--fov requiere un ángulo en grados")?;
+                    let fov = parse_float(value)?;
+                    if !(0.0..=120.0).contains(&fov) {
+                        return Err(format!("Campo de visión fuera de rango: {} (usa de 0 a 120)", value));
+                    }
+                    options.fov = fov;
+                }
+                "--affine" => options.interpolation = Interpolation::Affine,
+                "--star" => {
+                    let value = iter.next().ok_or_else(|| format!("--star requiere {}", star::PRESETS.join(", ")))?;
+                    options.star = Star::from_name(value)
+                        .ok_or_else(|| format!("Estrella desconocida: {}", value))?;
+                }
+                "--star-temperature" => {
+                    let value = iter.next().ok_or("--star-temperature requiere una temperatura en kelvin")?;
+                    let kelvin = parse_float(value)?;
+                    if !(1000.0..=40000.0).contains(&kelvin) {
+                        return Err(format!("Temperatura fuera de rango: {} (usa de 1000 a 40000 K)", value));
+                    }
+                    star_temperature = Some(kelvin);
+                }
+                "--star-luminosity" => {
+                    let value = iter.next().ok_or("--star-luminosity requiere un valor")?;
+                    star_luminosity = Some(parse_float(value)?.max(0.0));
+                }
+                "--no-early-z" => options.early_depth_test = false,
+                "--bench" => {
+                    let value = iter.next().ok_or("--bench requiere el número de frames")?;
+                    options.bench_frames = Some(parse_count(value)?);
+                }
+                "--seed" => {
+                    let value = iter.next().ok_or("--seed requiere un número")?;
+                    options.seed = Some(value.parse::<u64>().map_err(|_| format!("Semilla inválida: {}", value))?);
+                }
+                other => return Err(format!("Argumento desconocido: {}", other)),
+            }
+        }
+
+        if let Some(kelvin) = star_temperature {
+            options.star = options.star.with_temperature(kelvin);
+        }
+        if let Some(luminosity) = star_luminosity {
+            options.star = options.star.with_luminosity(luminosity);
+        }
+
+        if options.recording.writes_to_stdout() {
+            if options.recording.format != RecordFormat::Y4m {
+                return Err("--out - solo está disponible con --format y4m".to_string());
+            }
+            if options.headless_frames.is_none() {
+                return Err("--out - solo está disponible en modo --headless".to_string());
+            }
+        }
+
+        Ok(options)
+    }
+
+    /// Factor de supersampling por eje: `--samples 4` renderiza a 2x2 pixeles por pixel final
+    pub fn supersampling(&self) -> usize {
+        self.samples.isqrt()
+    }
+}
+
+fn parse_float(value: &str) -> Result<f32, String> {
+    value
+        .parse::<f32>()
+        .map_err(|_| format!("Valor inválido: {}", value))
+}
+
+fn parse_count(value: &str) -> Result<usize, String> {
+    value
+        .parse::<usize>()
+        .map_err(|_| format!("Número inválido: {}", value))
+}
@@ -0,0 +1,257 @@
+// params.rs
+// Parámetros de los shaders de cada cuerpo: paletas, escalas y octavas del ruido y umbrales.
+// Cada cuerpo guarda los suyos, así dos cuerpos con el mismo shader pueden verse distintos
+
+use crate::color::{Gradient, LinearColor};
+
+// Planeta rocoso: continentes, océanos, nubes y casquetes polares
+#[derive(Debug, Clone)]
+pub struct RockyParams {
+    // Tierra según la elevación
+    pub land: Gradient,
+    // Océano según el ruido de continentes: profundo lejos de la costa, claro cerca
+    pub ocean: Gradient,
+    pub ice: LinearColor,
+    // Frecuencia del ruido de continentes; la elevación usa `detail_scale`
+    pub noise_scale: f32,
+    pub detail_scale: f32,
+    pub octaves: u32,
+    // Valor del ruido sobre el que hay tierra (más alto = más océano)
+    pub sea_level: f32,
+    // Fracción del cielo cubierta por nubes (0 = despejado)
+    pub cloud_coverage: f32,
+    // Extensión de cada casquete desde su polo, en fracción del meridiano (0 = sin hielo)
+    pub ice_cap_latitude: f32,
+    // Velocidad con la que gira la superficie
+    pub spin: f32,
+    // Semilla de variación: desplaza el ruido, así dos cuerpos con los mismos parámetros no
+    // son idénticos (0 deja el patrón original)
+    pub seed: u32,
+}
+
+impl RockyParams {
+    // Tipo Tierra: océanos, continentes verdes y nubes
+    pub fn earth() -> Self {
+        let sand = LinearColor::from_srgb8(220, 200, 150);
+        let grass = LinearColor::from_srgb8(60, 140, 60);
+        let forest = LinearColor::from_srgb8(30, 100, 40);
+        let mountain = LinearColor::from_srgb8(120, 120, 120);
+        RockyParams {
+            land: Gradient::new(vec![
+                (0.43, sand),
+                (0.47, grass),
+                (0.53, grass),
+                (0.57, forest),
+                (0.68, forest),
+                (0.72, mountain),
+            ]),
+            ocean: Gradient::new(vec![
+                (0.40, LinearColor::from_srgb8(20, 80, 180)),
+                (0.48, LinearColor::from_srgb8(40, 120, 200)),
+            ]),
+            ice: LinearColor::from_srgb8(240, 250, 255),
+            noise_scale: 3.0,
+            detail_scale: 10.0,
+            octaves: 5,
+            sea_level: 0.5,
+            cloud_coverage: 0.4,
+            ice_cap_latitude: 0.15,
+            spin: 0.1,
+            seed: 0,
+        }
+    }
+
+    // Tipo Marte: desierto rojizo sin océanos, casquetes pequeños y casi sin nubes
+    pub fn mars() -> Self {
+        RockyParams {
+            land: Gradient::new(vec![
+                (0.35, LinearColor::from_srgb8(120, 50, 30)),
+                (0.5, LinearColor::from_srgb8(190, 90, 50)),
+                (0.65, LinearColor::from_srgb8(210, 130, 80)),
+            ]),
+            ocean: Gradient::new(vec![(0.0, LinearColor::from_srgb8(120, 50, 30))]),
+            ice: LinearColor::from_srgb8(245, 235, 230),
+            noise_scale: 4.0,
+            detail_scale: 6.0,
+            octaves: 5,
+            sea_level: 0.0,
+            cloud_coverage: 0.05,
+            ice_cap_latitude: 0.07,
+            spin: 0.08,
+            seed: 0,
+        }
+    }
+}
+
+impl Default for RockyParams {
+    fn default() -> Self {
+        Self::earth()
+    }
+}
+
+// Gigante gaseoso: bandas horizontales con turbulencia y una tormenta
+#[derive(Debug, Clone)]
+pub struct GasGiantParams {
+    // Color de las bandas, de la más oscura a la más clara
+    pub bands: Gradient,
+    pub storm_color: LinearColor,
+    // Bandas por unidad de latitud
+    pub band_frequency: f32,
+    pub turbulence_scale: f32,
+    pub octaves: u32,
+    // Centro de la tormenta (x, y en la esfera) y su radio (0 = sin tormenta)
+    pub storm_center: (f32, f32),
+    pub storm_radius: f32,
+    pub spin: f32,
+    pub seed: u32,
+}
+
+impl GasGiantParams {
+    // Tipo Júpiter: bandas marrones y crema con la Gran Mancha Roja
+    pub fn jupiter() -> Self {
+        GasGiantParams {
+            bands: Gradient::new(vec![
+                (0.0, LinearColor::from_srgb8(180, 130, 90)),
+                (0.45, LinearColor::from_srgb8(200, 150, 100)),
+                (0.55, LinearColor::from_srgb8(200, 150, 100)),
+                (1.0, LinearColor::from_srgb8(220, 200, 170)),
+            ]),
+            storm_color: LinearColor::from_srgb8(200, 80, 60),
+            band_frequency: 8.0,
+            turbulence_scale: 10.0,
+            octaves: 4,
+            storm_center: (0.3, -0.1),
+            storm_radius: 0.4,
+            spin: 0.05,
+            seed: 0,
+        }
+    }
+}
+
+impl Default for GasGiantParams {
+    fn default() -> Self {
+        Self::jupiter()
+    }
+}
+
+// Planeta helado: placas de hielo con grietas
+#[derive(Debug, Clone)]
+pub struct IceParams {
+    // Color del hielo según el ruido
+    pub palette: Gradient,
+    pub noise_scale: f32,
+    pub octaves: u32,
+    pub crack_scale: f32,
+    // Valor del ruido de grietas sobre el que el hielo se oscurece (1 = sin grietas)
+    pub crack_threshold: f32,
+    pub spin: f32,
+    pub seed: u32,
+}
+
+impl Default for IceParams {
+    fn default() -> Self {
+        let deep_blue = LinearColor::from_srgb8(100, 150, 220);
+        let ice_blue = LinearColor::from_srgb8(180, 220, 255);
+        let white = LinearColor::from_srgb8(230, 240, 255);
+        IceParams {
+            palette: Gradient::new(vec![
+                (0.4, deep_blue),
+                (0.4, ice_blue),
+                (0.6, ice_blue),
+                (0.6, white),
+            ]),
+            noise_scale: 8.0,
+            octaves: 4,
+            crack_scale: 20.0,
+            crack_threshold: 0.7,
+            spin: 0.15,
+            seed: 0,
+        }
+    }
+}
+
+// Planeta volcánico: roca oscura con ríos de lava que emiten luz
+#[derive(Debug, Clone)]
+pub struct VolcanicParams {
+    pub dark_rock: LinearColor,
+    pub light_rock: LinearColor,
+    // La lava va de `lava_cool` a `lava_hot` según su actividad
+    pub lava_cool: LinearColor,
+    pub lava_hot: LinearColor,
+    pub lava_scale: f32,
+    pub rock_scale: f32,
+    pub octaves: u32,
+    // Valor del ruido sobre el que hay lava (más alto = menos lava)
+    pub lava_threshold: f32,
+    // Emisión de la lava en el buffer HDR
+    pub lava_intensity: f32,
+    pub seed: u32,
+}
+
+impl Default for VolcanicParams {
+    fn default() -> Self {
+        VolcanicParams {
+            dark_rock: LinearColor::from_srgb8(40, 30, 30),
+            light_rock: LinearColor::from_srgb8(80, 70, 70),
+            lava_cool: LinearColor::from_srgb8(255, 120, 30),
+            lava_hot: LinearColor::from_srgb8(255, 200, 50),
+            lava_scale: 5.0,
+            rock_scale: 10.0,
+            octaves: 4,
+            lava_threshold: 0.6,
+            lava_intensity: 1.5,
+            seed: 0,
+        }
+    }
+}
+
+// Luna: superficie gris con cráteres
+#[derive(Debug, Clone)]
+pub struct MoonParams {
+    // Color según el ruido de cráteres: valles, tierras altas y cráteres
+    pub palette: Gradient,
+    pub crater_scale: f32,
+    pub octaves: u32,
+    pub seed: u32,
+}
+
+impl Default for MoonParams {
+    fn default() -> Self {
+        let dark_gray = LinearColor::from_srgb8(120, 120, 120);
+        let light_gray = LinearColor::from_srgb8(200, 200, 200);
+        let crater = LinearColor::from_srgb8(80, 80, 80);
+        MoonParams {
+            palette: Gradient::new(vec![
+                (0.4, dark_gray),
+                (0.4, light_gray),
+                (0.7, light_gray),
+                (0.7, crater),
+            ]),
+            crater_scale: 15.0,
+            octaves: 4,
+            seed: 0,
+        }
+    }
+}
+
+// Anillos: bandas concéntricas con huecos transparentes
+#[derive(Debug, Clone)]
+pub struct RingParams {
+    pub light: LinearColor,
+    pub dark: LinearColor,
+    // Bandas por unidad de radio (en radios del planeta)
+    pub band_frequency: f32,
+    // Valor de la onda de bandas sobre el que hay un hueco (1 = sin huecos)
+    pub gap_threshold: f32,
+}
+
+impl Default for RingParams {
+    fn default() -> Self {
+        RingParams {
+            light: LinearColor::from_srgb8(200, 180, 160),
+            dark: LinearColor::from_srgb8(120, 110, 100),
+            band_frequency: 20.0,
+            gap_threshold: 0.8,
+        }
+    }
+}
@@ -0,0 +1,184 @@
+// raster.rs
+// Rasterización por tiles en paralelo: los triángulos se reparten en los tiles que tocan
+// y cada hilo rasteriza y sombrea tiles completos, sin compartir pixeles con los demás
+
+use std::sync::Mutex;
+use std::thread;
+
+use crate::framebuffer::{Framebuffer, Tile};
+use crate::shader::FragmentShader;
+use crate::triangle::{calculate_bounding_box, triangle};
+use crate::vertex::Vertex;
+use crate::Uniforms;
+
+// Lado de cada tile en pixeles
+pub const TILE_SIZE: usize = 64;
+
+/// Hilos a usar por defecto: uno por núcleo disponible
+pub fn default_threads() -> usize {
+    thread::available_parallelism().map(|n| n.get()).unwrap_or(1)
+}
+
+/// Rasteriza `triangles` (índices a `vertices`) en `framebuffer` usando `threads` hilos.
+/// Dentro de cada tile los triángulos se procesan en el orden original, así el resultado
+/// es idéntico al serial.
+pub fn render_tiled(
+    framebuffer: &mut Framebuffer,
+    vertices: &[Vertex],
+    triangles: &[[usize; 3]],
+    shader: &dyn FragmentShader,
+    uniforms: &Uniforms,
+    threads: usize,
+) {
+    let columns = framebuffer.width.div_ceil(TILE_SIZE);
+    let rows = framebuffer.height.div_ceil(TILE_SIZE);
+    if columns == 0 || rows == 0 {
+        return;
+    }
+
+    // Binning: cada tile guarda los índices de los triángulos que lo tocan
+    let mut bins: Vec<Vec<usize>> = vec![Vec::new(); columns * rows];
+    for (i, tri) in triangles.iter().enumerate() {
+        let (min_x, min_y, max_x, max_y) = calculate_bounding_box(
+            &vertices[tri[0]].transformed_position,
+            &vertices[tri[1]].transformed_position,
+            &vertices[tri[2]].transformed_position,
+        );
+        if max_x < 0 || max_y < 0 {
+            continue;
+        }
+        let first_column = min_x.max(0) as usize / TILE_SIZE;
+        let first_row = min_y.max(0) as usize / TILE_SIZE;
+        let last_column = (max_x as usize / TILE_SIZE).min(columns - 1);
+        let last_row = (max_y as usize / TILE_SIZE).min(rows - 1);
+        for row in first_row..=last_row {
+            for column in first_column..=last_column {
+                bins[row * columns + column].push(i);
+            }
+        }
+    }
+
+    framebuffer.with_tiles(TILE_SIZE, |tiles| {
+        // Cola de trabajo compartida: cada hilo toma el siguiente tile con triángulos
+        let jobs = Mutex::new(
+            tiles
+                .iter_mut()
+                .zip(&bins)
+                .filter(|(_, bin)| !bin.is_empty()),
+        );
+
+        thread::scope(|scope| {
+            for _ in 0..threads.max(1) {
+                scope.spawn(|| loop {
+                    let job = jobs.lock().unwrap().next();
+                    let Some((tile, bin)) = job else {
+                        break;
+                    };
+                    rasterize_tile(tile, bin, vertices, triangles, shader, uniforms);
+                });
+            }
+        });
+    });
+}
+
+fn rasterize_tile(
+    tile: &mut Tile,
+    bin: &[usize],
+    vertices: &[Vertex],
+    triangles: &[[usize; 3]],
+    shader: &dyn FragmentShader,
+    uniforms: &Uniforms,
+) {
+    for &i in bin {
+        let [a, b, c] = triangles[i].map(|index| &vertices[index]);
+        triangle(a, b, c, shader, uniforms, tile);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra_glm::{Vec2, Vec3};
+
+    use super::*;
+    use crate::color::{LinearColor, LinearRgba};
+    use crate::framebuffer::BlendMode;
+    use crate::shader::{BodyParams, ShadingContext};
+
+    // Color según la posición interpolada: cualquier diferencia de cobertura o de orden se ve
+    struct PositionShader {
+        alpha: f32,
+    }
+
+    impl FragmentShader for PositionShader {
+        fn shade(&self, ctx: &ShadingContext) -> LinearRgba {
+            let p = ctx.object_position;
+            LinearRgba::new(LinearColor::new(p.x / 200.0, p.y / 150.0, p.z), self.alpha)
+        }
+    }
+
+    // Triángulos superpuestos a distintas profundidades que cruzan los bordes de los tiles
+    // de 64 px, tocan esos bordes exactamente o se salen de la pantalla
+    fn scene() -> (Vec<Vertex>, Vec<[usize; 3]>) {
+        let corners = [
+            [(-20.0, -10.0, 0.9), (230.0, 20.0, 0.9), (40.0, 170.0, 0.9)],
+            [(10.0, 10.0, 0.5), (130.0, 30.0, 0.2), (60.0, 140.0, 0.7)],
+            [(64.0, 0.0, 0.3), (128.0, 64.0, 0.3), (64.0, 128.0, 0.3)],
+            [(63.5, 63.5, 0.1), (190.0, 70.0, 0.6), (120.0, 149.0, 0.4)],
+            [(150.0, 5.0, 0.05), (199.0, 60.0, 0.05), (140.0, 127.9, 0.05)],
+            [(0.0, 64.0, 0.25), (64.0, 64.0, 0.25), (0.0, 128.0, 0.25)],
+            [(64.0, 64.0, 0.25), (64.0, 128.0, 0.25), (0.0, 128.0, 0.25)],
+        ];
+        let vertices = corners
+            .iter()
+            .flatten()
+            .map(|&(x, y, z)| Vertex::new(Vec3::new(x, y, z), Vec3::new(0.0, 0.0, -1.0), Vec2::new(0.0, 0.0)))
+            .collect();
+        let triangles = (0..corners.len()).map(|i| [3 * i, 3 * i + 1, 3 * i + 2]).collect();
+        (vertices, triangles)
+    }
+
+    // Dos triángulos semitransparentes que se cruzan sobre varios tiles, como los anillos
+    fn translucent() -> (Vec<Vertex>, Vec<[usize; 3]>) {
+        let corners = [
+            (5.0, 100.0, 0.15), (195.0, 90.0, 0.35), (100.0, 20.0, 0.25),
+            (30.0, 20.0, 0.2), (180.0, 140.0, 0.2), (170.0, 10.0, 0.2),
+        ];
+        let vertices = corners.map(|(x, y, z)| Vertex::new(Vec3::new(x, y, z), Vec3::new(0.0, 0.0, -1.0), Vec2::new(0.0, 0.0)));
+        (vertices.to_vec(), vec![[0, 1, 2], [3, 4, 5]])
+    }
+
+    fn draw(threads: Option<usize>) -> Framebuffer {
+        let params = BodyParams::default();
+        let uniforms = Uniforms::for_tests(&params);
+        let mut framebuffer = Framebuffer::new(200, 150);
+        let passes = [(scene(), BlendMode::Opaque, 1.0), (translucent(), BlendMode::Alpha, 0.5)];
+        for ((vertices, triangles), mode, alpha) in passes {
+            let shader = PositionShader { alpha };
+            framebuffer.set_blend_mode(mode);
+            match threads {
+                Some(threads) => render_tiled(&mut framebuffer, &vertices, &triangles, &shader, &uniforms, threads),
+                // El camino serial de `render`: cada triángulo directo al framebuffer
+                None => {
+                    for tri in &triangles {
+                        let [a, b, c] = tri.map(|index| &vertices[index]);
+                        triangle(a, b, c, &shader, &uniforms, &mut framebuffer);
+                    }
+                }
+            }
+        }
+        framebuffer.resolve();
+        framebuffer
+    }
+
+    #[test]
+    fn tiled_output_matches_serial() {
+        let serial = draw(None);
+        assert!(serial.overdraw.iter().any(|&count| count > 1), "la escena debe tener superposición");
+        for threads in [1, 4] {
+            let tiled = draw(Some(threads));
+            assert_eq!(tiled.buffer, serial.buffer, "color con {threads} hilos");
+            assert_eq!(tiled.zbuffer, serial.zbuffer, "profundidad con {threads} hilos");
+            assert_eq!(tiled.overdraw, serial.overdraw, "overdraw con {threads} hilos");
+        }
+    }
+}
@@ -0,0 +1,198 @@
+// recorder.rs
+// Grabación de la simulación: GIF animado, video Y4M o secuencia numerada de imágenes
+
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::export::{self, ImageFormat};
+use crate::framebuffer::Framebuffer;
+use crate::gif::GifEncoder;
+use crate::y4m::Y4mWriter;
+use crate::TIME_STEP;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum RecordFormat {
+    Gif,
+    Y4m,
+    Sequence(ImageFormat),
+}
+
+impl RecordFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        if name.eq_ignore_ascii_case("gif") {
+            Some(RecordFormat::Gif)
+        } else if name.eq_ignore_ascii_case("y4m") {
+            Some(RecordFormat::Y4m)
+        } else {
+            ImageFormat::from_name(name).map(RecordFormat::Sequence)
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self {
+            RecordFormat::Gif => "gif",
+            RecordFormat::Y4m => "y4m",
+            RecordFormat::Sequence(format) => format.extension(),
+        }
+    }
+}
+
+/// Configuración compartida por las grabaciones del modo ventana y del modo sin ventana
+#[derive(Clone, Debug)]
+pub struct RecordingSettings {
+    pub output_dir: PathBuf,
+    pub format: RecordFormat,
+    pub every: usize,
+}
+
+impl RecordingSettings {
+    /// `--out -` envía el stream a la salida estándar (solo Y4M)
+    pub fn writes_to_stdout(&self) -> bool {
+        self.output_dir.as_os_str() == "-"
+    }
+}
+
+impl Default for RecordingSettings {
+    fn default() -> Self {
+        RecordingSettings {
+            output_dir: PathBuf::from("frames"),
+            format: RecordFormat::Sequence(ImageFormat::Png),
+            every: 1,
+        }
+    }
+}
+
+enum Sink {
+    Gif(GifEncoder<BufWriter<File>>),
+    Y4m(Y4mWriter<Box<dyn Write>>),
+    Sequence { dir: PathBuf, format: ImageFormat },
+}
+
+pub struct Recorder {
+    sink: Sink,
+    path: PathBuf,
+    every: usize,
+    frame_index: usize,
+    captured: usize,
+}
+
+impl Recorder {
+    /// Inicia una grabación. Con `session` el resultado va a `<dir>/<session>.gif|y4m`
+    /// o `<dir>/<session>/`; sin ella, a `<dir>/recording.gif|y4m` o directamente a `<dir>/`.
+    pub fn start(
+        settings: &RecordingSettings,
+        session: Option<&str>,
+        width: usize,
+        height: usize,
+    ) -> io::Result<Self> {
+        let every = settings.every.max(1);
+
+        if settings.writes_to_stdout() {
+            if settings.format != RecordFormat::Y4m {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "solo el formato y4m puede escribirse a la salida estándar",
+                ));
+            }
+            let writer: Box<dyn Write> = Box::new(BufWriter::new(io::stdout()));
+            let (fps_num, fps_den) = frame_rate(every);
+            return Ok(Recorder {
+                sink: Sink::Y4m(Y4mWriter::new(writer, width, height, fps_num, fps_den)?),
+                path: PathBuf::from("-"),
+                every,
+                frame_index: 0,
+                captured: 0,
+            });
+        }
+
+        fs::create_dir_all(&settings.output_dir)?;
+
+        let file_path = settings.output_dir.join(format!(
+            "{}.{}",
+            session.unwrap_or("recording"),
+            settings.format.extension()
+        ));
+
+        let (sink, path) = match settings.format {
+            RecordFormat::Gif => {
+                let writer = BufWriter::new(File::create(&file_path)?);
+                (Sink::Gif(GifEncoder::new(writer, width, height)?), file_path)
+            }
+            RecordFormat::Y4m => {
+                let writer: Box<dyn Write> = Box::new(BufWriter::new(File::create(&file_path)?));
+                let (fps_num, fps_den) = frame_rate(every);
+                (Sink::Y4m(Y4mWriter::new(writer, width, height, fps_num, fps_den)?), file_path)
+            }
+            RecordFormat::Sequence(format) => {
+                let dir = match session {
+                    Some(name) => settings.output_dir.join(name),
+                    None => settings.output_dir.clone(),
+                };
+                fs::create_dir_all(&dir)?;
+                (Sink::Sequence { dir: dir.clone(), format }, dir)
+            }
+        };
+
+        Ok(Recorder {
+            sink,
+            path,
+            every,
+            frame_index: 0,
+            captured: 0,
+        })
+    }
+
+    /// Captura el framebuffer si toca en este frame (uno de cada `every`)
+    pub fn capture(&mut self, framebuffer: &Framebuffer) -> io::Result<()> {
+        let frame = self.frame_index;
+        self.frame_index += 1;
+        if !frame.is_multiple_of(self.every) {
+            return Ok(());
+        }
+
+        match &mut self.sink {
+            Sink::Gif(encoder) => {
+                // El retardo sigue el paso fijo de la simulación, no el reloj real
+                let delay_cs = (TIME_STEP * self.every as f32 * 100.0).round().max(2.0) as u16;
+                encoder.add_frame(&framebuffer.buffer, delay_cs)?;
+            }
+            Sink::Y4m(writer) => writer.write_frame(&framebuffer.buffer)?,
+            Sink::Sequence { dir, format } => {
+                let file_name = format!("frame_{:05}.{}", self.captured, format.extension());
+                export::save_frame(framebuffer, &dir.join(file_name), *format)?;
+            }
+        }
+
+        self.captured += 1;
+        Ok(())
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn captured(&self) -> usize {
+        self.captured
+    }
+
+    /// Termina la grabación (cierra el GIF si corresponde)
+    pub fn finish(self) -> io::Result<()> {
+        match self.sink {
+            Sink::Gif(encoder) => {
+                encoder.finish()?;
+            }
+            Sink::Y4m(writer) => {
+                writer.finish()?;
+            }
+            Sink::Sequence { .. } => {}
+        }
+        Ok(())
+    }
+}
+
+// Frames por segundo como fracción, siguiendo el paso fijo `TIME_STEP` (no el reloj real)
+fn frame_rate(every: usize) -> (u32, u32) {
+    let frame_ms = (TIME_STEP * 1000.0).round() as u32;
+    (1000, frame_ms * every as u32)
+}
@@ -1,318 +1,471 @@
-use nalgebra_glm::Vec3;
-use crate::color::Color;
+use nalgebra_glm::{Vec2, Vec3, dot};
+use crate::color::{LinearColor, LinearRgba};
+use crate::fragment::Varyings;
+use crate::light::Light;
+use crate::noise::{fbm, fbm4, seed_offset};
+use crate::params::{GasGiantParams, IceParams, MoonParams, RingParams, RockyParams, VolcanicParams};
+use crate::star::Star;
+use crate::Uniforms;
 use std::f32::consts::PI;
 
 // ============= FUNCIONES DE RUIDO PROCEDURAL =============
+// El ruido está en `noise.rs`; los planetas lo muestrean sobre su superficie
 
-fn random(x: f32, y: f32) -> f32 {
-    let a = x * 12.9898 + y * 78.233;
-    let b = a.sin() * 43758.5453;
-    b - b.floor()
+// Punto de la esfera unitaria bajo `position`, girado `angle` radianes alrededor del eje de
+// los polos (z): así gira la superficie sin mover el modelo
+fn surface_point(position: &Vec3, angle: f32) -> Vec3 {
+    let p = position.try_normalize(f32::EPSILON).unwrap_or(*position);
+    let (sin, cos) = angle.sin_cos();
+    Vec3::new(p.x * cos - p.y * sin, p.x * sin + p.y * cos, p.z)
 }
 
-fn noise(x: f32, y: f32) -> f32 {
-    let i_x = x.floor();
-    let i_y = y.floor();
-    let f_x = x - i_x;
-    let f_y = y - i_y;
-
-    let a = random(i_x, i_y);
-    let b = random(i_x + 1.0, i_y);
-    let c = random(i_x, i_y + 1.0);
-    let d = random(i_x + 1.0, i_y + 1.0);
-
-    let u = f_x * f_x * (3.0 - 2.0 * f_x);
-    let v = f_y * f_y * (3.0 - 2.0 * f_y);
-
-    a * (1.0 - u) * (1.0 - v) + b * u * (1.0 - v) + c * (1.0 - u) * v + d * u * v
-}
-
-fn fbm(x: f32, y: f32, octaves: u32) -> f32 {
-    let mut value = 0.0;
-    let mut amplitude = 0.5;
-    let mut frequency = 1.0;
-
-    for _ in 0..octaves {
-        value += amplitude * noise(x * frequency, y * frequency);
-        amplitude *= 0.5;
-        frequency *= 2.0;
-    }
-
-    value
-}
+// Intensidad de emisión del sol en el buffer HDR
+const SUN_INTENSITY: f32 = 4.0;
 
 // ============= SHADER 1: SOL (ESTRELLA) =============
 // Capas: gradiente radial, manchas solares, llamaradas, corona
-pub fn sun_shader(position: &Vec3, time: f32) -> Color {
+pub fn sun_shader(ctx: &ShadingContext, star: &Star) -> LinearColor {
+    let (position, time) = (&ctx.object_position, ctx.time);
+    
     // Distancia desde el centro
     let distance = (position.x * position.x + position.y * position.y + position.z * position.z).sqrt();
     
-    // Capa 1: Gradiente radial (centro amarillo brillante, borde naranja-rojo)
+    // Capa 1: Gradiente radial (centro caliente y claro, borde más frío y rojizo)
     let radial_gradient = 1.0 - (distance * 0.8).min(1.0);
     
     // Capa 2: Manchas solares (usando ruido)
-    let sunspot_noise = fbm(position.x * 3.0 + time * 0.5, position.y * 3.0, 4);
+    let sunspot_noise = fbm(&(position * 3.0 + Vec3::new(time * 0.5, 0.0, 0.0)), 4);
     let sunspots = if sunspot_noise > 0.6 { 0.7 } else { 1.0 };
     
     // Capa 3: Llamaradas (animadas)
-    let flare_noise = fbm(
-        position.x * 5.0 + time * 2.0,
-        position.y * 5.0 + (time * 1.5).sin() * 0.5,
-        3
-    );
+    let flare_noise = fbm(&(position * 5.0 + Vec3::new(time * 2.0, (time * 1.5).sin() * 0.5, 0.0)), 3);
     let flares = (flare_noise * 0.3 + 0.7).clamp(0.5, 1.0);
     
     // Capa 4: Corona (brillo en los bordes)
     let corona = (1.0_f32 - distance).powf(0.3) * 0.5;
     
-    // Mezcla de colores base
-    let yellow = Color::new(255, 220, 100);
-    let orange = Color::new(255, 150, 50);
-    let red = Color::new(255, 80, 30);
-    let white = Color::new(255, 255, 255);
+    // Colores base según la temperatura de la estrella
+    let (core, mid, limb) = star.disc_colors();
+    let white = LinearColor::white();
     
-    // Interpola entre colores según la distancia
+    // Interpola entre colores según la distancia (en espacio lineal)
     let base_color = if radial_gradient > 0.7 {
-        // Centro: amarillo-blanco
-        yellow * 0.7 + white * 0.3
+        // Centro
+        core
     } else if radial_gradient > 0.4 {
-        // Medio: amarillo-naranja
-        yellow * (radial_gradient - 0.4) * 3.33 + orange * (1.0 - (radial_gradient - 0.4) * 3.33)
+        // Medio
+        mid.lerp(core, (radial_gradient - 0.4) * 3.33)
     } else {
-        // Borde: naranja-rojo
-        orange * (radial_gradient * 2.5) + red * (1.0 - radial_gradient * 2.5)
+        // Borde
+        limb.lerp(mid, radial_gradient * 2.5)
     };
     
     // Aplica todas las capas
     let final_color = base_color * sunspots * flares;
     
-    // Añade corona brillante
-    final_color + white * corona
+    // El sol emite luz: su brillo supera 1.0 y el tone mapping lo comprime
+    let emission = final_color * SUN_INTENSITY * star.luminosity;
+    
+    // Añade corona brillante, teñida por el color del centro
+    emission + white.lerp(core, 0.5) * corona * star.luminosity
 }
 
 // ============= SHADER 2: PLANETA ROCOSO (TIPO TIERRA/MARTE) =============
 // Capas: continentes, océanos, nubes, casquetes polares
-pub fn rocky_planet_shader(position: &Vec3, time: f32) -> Color {
-    // Usa coordenadas esféricas para mapeo consistente
-    let theta = position.y.atan2(position.x) + time * 0.1; // Rotación lenta
-    let phi = (position.z / (position.x * position.x + position.y * position.y + position.z * position.z).sqrt()).acos();
+pub fn rocky_planet_shader(ctx: &ShadingContext, params: &RockyParams) -> LinearColor {
+    let (position, time) = (&ctx.object_position, ctx.time);
+    let seed = seed_offset(params.seed);
+    
+    // Punto de la superficie, con rotación lenta
+    let surface = surface_point(position, time * params.spin);
     
     // Capa 1: Continentes vs Océanos
-    let land_noise = fbm(theta * 3.0, phi * 3.0, 5);
-    let is_land = land_noise > 0.5;
+    let land_noise = fbm(&(surface * params.noise_scale + seed), params.octaves);
+    let is_land = land_noise > params.sea_level;
     
     // Capa 2: Variación de elevación en continentes
-    let elevation = fbm(theta * 10.0, phi * 10.0, 3);
+    let elevation = fbm(&(surface * params.detail_scale + seed), 3);
     
-    // Capa 3: Nubes
-    let cloud_noise = fbm(theta * 5.0 - time * 0.5, phi * 5.0, 3);
-    let clouds = if cloud_noise > 0.6 { 0.3 } else { 0.0 };
+    // Capa 3: Nubes: giran más lento que la superficie y cambian de forma con el tiempo
+    let sky = surface_point(position, time * (params.spin - 0.1));
+    let cloud_noise = fbm4(&(sky * 5.0 + seed), time * 0.1, 3);
+    let clouds = if cloud_noise > 1.0 - params.cloud_coverage { 0.3 } else { 0.0 };
     
     // Capa 4: Casquetes polares
-    let pole_factor = (phi / PI).abs();
-    let is_pole = pole_factor < 0.15 || pole_factor > 0.85;
-    
-    // Colores base
-    let ocean = Color::new(20, 80, 180);       // Azul océano
-    let shallow = Color::new(40, 120, 200);     // Azul claro
-    let sand = Color::new(220, 200, 150);       // Arena
-    let grass = Color::new(60, 140, 60);        // Verde
-    let forest = Color::new(30, 100, 40);       // Verde oscuro
-    let mountain = Color::new(120, 120, 120);   // Gris montañas
-    let snow = Color::new(240, 250, 255);       // Blanco nieve
-    let white_cloud = Color::new(255, 255, 255);
+    let pole_factor = surface.z.clamp(-1.0, 1.0).acos() / PI;
+    let is_pole = pole_factor < params.ice_cap_latitude || pole_factor > 1.0 - params.ice_cap_latitude;
     
     // Determina el color base
     let base_color = if is_pole {
-        snow
+        params.ice
     } else if is_land {
         // Tierra con variación de elevación
-        if elevation > 0.7 {
-            mountain
-        } else if elevation > 0.55 {
-            forest
-        } else if elevation > 0.45 {
-            grass
-        } else {
-            sand
-        }
+        params.land.sample(elevation)
     } else {
         // Océanos con profundidad
-        if land_noise > 0.45 {
-            shallow
-        } else {
-            ocean
-        }
+        params.ocean.sample(land_noise)
     };
     
-    // Aplica nubes
-    let final_color = base_color * (1.0 - clouds) + white_cloud * clouds;
-    
-    final_color
+    // Aplica nubes: screen con blanco por `clouds` equivale a lerp(base, blanco, clouds),
+    // una interpolación hacia blanco
+    base_color.screen(LinearColor::white() * clouds)
 }
 
 // ============= SHADER 3: GIGANTE GASEOSO (TIPO JÚPITER) =============
 // Capas: bandas horizontales, tormenta, turbulencia, variación de color
-pub fn gas_giant_shader(position: &Vec3, time: f32) -> Color {
+pub fn gas_giant_shader(ctx: &ShadingContext, params: &GasGiantParams) -> LinearColor {
+    let (position, time) = (&ctx.object_position, ctx.time);
+    let seed = seed_offset(params.seed);
+    let surface = surface_point(position, 0.0);
+    
     // Usa latitud para bandas horizontales
-    let latitude = position.y + time * 0.05; // Rotación lenta
+    let latitude = position.y + time * params.spin; // Rotación lenta
     
     // Capa 1: Bandas principales
-    let band = (latitude * 8.0).sin() * 0.5 + 0.5;
+    let band = (latitude * params.band_frequency).sin() * 0.5 + 0.5;
     
-    // Capa 2: Turbulencia en las bandas
+    // Capa 2: Turbulencia en las bandas, estirada a lo largo de ellas y arrastrada por el viento
+    let wind = Vec3::new(time * 0.3, 0.0, 0.0);
     let turbulence = fbm(
-        position.x * 10.0 + time * 0.3,
-        latitude * 5.0,
-        4
+        &(Vec3::new(surface.x, latitude * 0.5, surface.z) * params.turbulence_scale + wind + seed),
+        params.octaves
     );
     
-    // Capa 3: Gran Mancha Roja (tormenta)
-    let spot_x = position.x - 0.3;
-    let spot_y = position.y + 0.1;
+    // Capa 3: Tormenta (tipo Gran Mancha Roja)
+    let spot_x = position.x - params.storm_center.0;
+    let spot_y = position.y - params.storm_center.1;
     let spot_distance = (spot_x * spot_x + spot_y * spot_y).sqrt();
-    let storm = if spot_distance < 0.4 {
-        let storm_noise = fbm(position.x * 20.0 + time, position.y * 20.0, 2);
-        (1.0 - spot_distance / 0.4) * storm_noise
+    let storm = if spot_distance < params.storm_radius {
+        let storm_noise = fbm4(&(surface * 20.0), time, 2);
+        (1.0 - spot_distance / params.storm_radius) * storm_noise
     } else {
         0.0
     };
     
     // Capa 4: Variación de intensidad en bandas
-    let intensity_variation = fbm(position.x * 15.0, latitude * 8.0, 2);
-    
-    // Colores de las bandas
-    let light_band = Color::new(220, 200, 170);  // Crema claro
-    let dark_band = Color::new(180, 130, 90);    // Marrón
-    let orange_band = Color::new(200, 150, 100); // Naranja
-    let red_storm = Color::new(200, 80, 60);     // Rojo tormenta
+    let intensity_variation = fbm(
+        &(Vec3::new(surface.x * 15.0, latitude * params.band_frequency, surface.z * 15.0) + seed),
+        2
+    );
     
     // Mezcla bandas claras y oscuras
-    let band_color = if band > 0.5 {
-        light_band * (band * 1.5).min(1.0) + orange_band * (1.0 - band)
-    } else {
-        dark_band * ((1.0_f32 - band) * 1.5).min(1.0) + orange_band * band
-    };
+    let band_color = params.bands.sample(band);
     
-    // Aplica turbulencia
-    let turbulent_color = band_color * (0.8 + turbulence * 0.4);
+    // Aplica turbulencia: overlay con gris medio (0.5) deja la banda igual
+    let turbulent_color = band_color.overlay(LinearColor::white() * (0.3 + turbulence * 0.4));
     
     // Aplica tormenta
-    let final_color = turbulent_color * (1.0 - storm) + red_storm * storm;
+    let final_color = turbulent_color.lerp(params.storm_color, storm);
     
     // Aplica variación de intensidad
     final_color * (0.7 + intensity_variation * 0.3)
 }
 
 // ============= SHADER 4: PLANETA HELADO (BONUS - TIPO URANO/NEPTUNO) =============
-pub fn ice_planet_shader(position: &Vec3, time: f32) -> Color {
-    let theta = position.y.atan2(position.x) + time * 0.15;
-    let phi = (position.z / (position.x * position.x + position.y * position.y + position.z * position.z).sqrt()).acos();
+pub fn ice_planet_shader(ctx: &ShadingContext, params: &IceParams) -> LinearColor {
+    let seed = seed_offset(params.seed);
+    let surface = surface_point(&ctx.object_position, ctx.time * params.spin);
     
     // Capa de hielo con grietas
-    let ice_noise = fbm(theta * 8.0, phi * 8.0, 4);
-    let cracks = fbm(theta * 20.0, phi * 20.0, 2);
-    
-    // Colores
-    let ice_blue = Color::new(180, 220, 255);
-    let deep_blue = Color::new(100, 150, 220);
-    let white = Color::new(230, 240, 255);
-    
-    let base = if ice_noise > 0.6 {
-        white
-    } else if ice_noise > 0.4 {
-        ice_blue
-    } else {
-        deep_blue
-    };
+    let ice_noise = fbm(&(surface * params.noise_scale + seed), params.octaves);
+    let cracks = fbm(&(surface * params.crack_scale + seed), 2);
+    
+    let base = params.palette.sample(ice_noise);
     
     // Añade grietas oscuras
-    let final_color = if cracks > 0.7 {
+    if cracks > params.crack_threshold {
         base * 0.7
     } else {
         base
-    };
-    
-    final_color
+    }
 }
 
 // ============= SHADER 5: PLANETA VOLCÁNICO (BONUS - TIPO IO) =============
-pub fn volcanic_planet_shader(position: &Vec3, time: f32) -> Color {
-    let theta = position.y.atan2(position.x);
-    let phi = (position.z / (position.x * position.x + position.y * position.y + position.z * position.z).sqrt()).acos();
+pub fn volcanic_planet_shader(ctx: &ShadingContext, params: &VolcanicParams) -> LinearColor {
+    let time = ctx.time;
+    let seed = seed_offset(params.seed);
+    let surface = surface_point(&ctx.object_position, 0.0);
     
-    // Lava activa (animada)
-    let lava_flow = fbm(theta * 5.0 + time * 2.0, phi * 5.0 + time, 3);
+    // Lava activa (animada: fluye en su lugar)
+    let lava_flow = fbm4(&(surface * params.lava_scale + seed), time, 3);
     
     // Roca volcánica
-    let rock_texture = fbm(theta * 10.0, phi * 10.0, 4);
-    
-    // Colores
-    let black_rock = Color::new(40, 30, 30);
-    let gray_rock = Color::new(80, 70, 70);
-    let lava_orange = Color::new(255, 120, 30);
-    let lava_yellow = Color::new(255, 200, 50);
+    let rock_texture = fbm(&(surface * params.rock_scale + seed), params.octaves);
     
     let rock_color = if rock_texture > 0.5 {
-        gray_rock
+        params.light_rock
     } else {
-        black_rock
+        params.dark_rock
     };
     
     // Lava activa
     let lava_intensity = (lava_flow * 0.5 + 0.5).clamp(0.0, 1.0);
-    let is_lava = lava_flow > 0.6;
+    let is_lava = lava_flow > params.lava_threshold;
     
     if is_lava {
-        let lava_color = lava_orange * (1.0 - lava_intensity) + lava_yellow * lava_intensity;
-        lava_color * (0.8 + (time * 5.0).sin() * 0.2)
+        let lava_color = params.lava_cool.lerp(params.lava_hot, lava_intensity);
+        // La lava emite luz propia por encima de 1.0
+        lava_color * params.lava_intensity * (0.8 + (time * 5.0).sin() * 0.2)
     } else {
         rock_color
     }
 }
 
 // ============= SHADER 6: ANILLOS (PARA GIGANTES GASEOSOS) =============
-pub fn ring_shader(position: &Vec3, distance_from_center: f32) -> Color {
+pub fn ring_shader(ctx: &ShadingContext, params: &RingParams) -> LinearRgba {
     // Los anillos son un plano alrededor del planeta
     // distance_from_center es la distancia radial en el plano XZ
+    let position = &ctx.object_position;
+    let distance_from_center = position.x.hypot(position.z);
+    // El alfa deja ver el planeta (y el espacio) a través de los anillos
     
     // Múltiples anillos con gaps
-    let ring_pattern = (distance_from_center * 20.0).sin();
-    let ring_noise = fbm(distance_from_center * 30.0, position.y * 50.0, 3);
+    let ring_pattern = (distance_from_center * params.band_frequency).sin();
+    let ring_noise = fbm(&Vec3::new(distance_from_center * 30.0, position.y * 50.0, 0.0), 3);
     
-    // Colores de anillos
-    let light_ring = Color::new(200, 180, 160);
-    let dark_ring = Color::new(120, 110, 100);
-    let gap = Color::new(0, 0, 0); // Transparente (negro)
+    let gap = LinearColor::black(); // Transparente
     
     // Determina si es gap o anillo
-    if ring_pattern > 0.8 {
-        gap
+    if ring_pattern > params.gap_threshold {
+        LinearRgba::new(gap, 0.0)
     } else if ring_noise > 0.6 {
-        light_ring * 0.8
+        LinearRgba::new(params.light * 0.8, 0.75)
     } else {
-        dark_ring * 0.6
+        LinearRgba::new(params.dark * 0.6, 0.45)
     }
 }
 
 // ============= SHADER 7: LUNA (SIMPLE - TIPO LUNA TERRESTRE) =============
-pub fn moon_shader(position: &Vec3) -> Color {
-    let theta = position.y.atan2(position.x);
-    let phi = (position.z / (position.x * position.x + position.y * position.y + position.z * position.z).sqrt()).acos();
+pub fn moon_shader(ctx: &ShadingContext, params: &MoonParams) -> LinearColor {
+    let surface = surface_point(&ctx.object_position, 0.0);
     
     // Cráteres
-    let crater_noise = fbm(theta * 15.0, phi * 15.0, 4);
-    
-    // Colores
-    let light_gray = Color::new(200, 200, 200);
-    let dark_gray = Color::new(120, 120, 120);
-    let crater = Color::new(80, 80, 80);
-    
-    if crater_noise > 0.7 {
-        crater
-    } else if crater_noise > 0.4 {
-        light_gray
-    } else {
-        dark_gray
+    let crater_noise = fbm(&(surface * params.crater_scale + seed_offset(params.seed)), params.octaves);
+    params.palette.sample(crater_noise)
+}
+
+// ============= SHADERS PROGRAMABLES =============
+// Cada cuerpo se dibuja con una implementación de `FragmentShader`. El rasterizador solo
+// interpola los atributos: el color y la iluminación son del shader, así que un aspecto nuevo
+// es un tipo nuevo que implementa el trait
+
+/// Parámetros propios de cada cuerpo, que llegan a cualquier shader por el contexto. Los del
+/// aspecto de la superficie (paletas, ruido, semilla) son del shader y están en `params.rs`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BodyParams {
+    // Fracción mínima de la luz en el lado nocturno: el cuerpo nunca queda en negro
+    pub ambient: f32,
+    // Intensidad del halo de atmósfera en el borde iluminado (0 = sin atmósfera)
+    pub atmosphere: f32,
+}
+
+impl BodyParams {
+    pub fn new(ambient: f32, atmosphere: f32) -> Self {
+        BodyParams { ambient, atmosphere }
+    }
+}
+
+impl Default for BodyParams {
+    fn default() -> Self {
+        Self::new(0.2, 0.0)
+    }
+}
+
+/// Todo lo que un fragment shader sabe del fragmento que sombrea
+pub struct ShadingContext<'a> {
+    // Normal interpolada en el espacio de mundo, normalizada
+    pub normal: Vec3,
+    pub tex_coords: Vec2,
+    // Posición en el espacio del modelo (sobre la esfera unitaria en los planetas)
+    pub object_position: Vec3,
+    // Posición en el espacio de mundo (pixeles del framebuffer en x, y)
+    pub world_position: Vec3,
+    // Dirección normalizada del fragmento hacia la cámara
+    pub view_direction: Vec3,
+    pub lights: &'a [Light],
+    pub time: f32,
+    pub params: &'a BodyParams,
+}
+
+impl<'a> ShadingContext<'a> {
+    pub fn new(varyings: &Varyings, uniforms: &'a Uniforms) -> Self {
+        // Cámara en el infinito (w = 0, ortográfica): la misma dirección para todo fragmento
+        let eye = uniforms.eye;
+        let view_direction = if eye.w == 0.0 {
+            eye.xyz().normalize()
+        } else {
+            (eye.xyz() - varyings.world_position).normalize()
+        };
+
+        ShadingContext {
+            normal: varyings.normal,
+            tex_coords: varyings.tex_coords,
+            object_position: varyings.object_position,
+            world_position: varyings.world_position,
+            view_direction,
+            lights: uniforms.lights,
+            time: uniforms.time,
+            params: uniforms.params,
+        }
+    }
+
+    /// Luz difusa (Lambert) que llega al fragmento, sumando todas las luces. `ambient` es la
+    /// fracción mínima de cada luz: el lado nocturno nunca queda en negro
+    pub fn diffuse(&self, ambient: f32) -> LinearColor {
+        self.lights.iter().fold(LinearColor::black(), |total, light| {
+            let intensity = dot(&self.normal, &light.direction_from(&self.world_position)).max(0.0);
+            total + light.color * intensity.max(ambient)
+        })
+    }
+
+    /// Borde de la silueta: 0 de frente a la cámara y 1 donde la superficie se ve de canto
+    pub fn rim(&self, power: f32) -> f32 {
+        (1.0 - dot(&self.normal, &self.view_direction).max(0.0)).powf(power)
     }
-}
\ No newline at end of file
+}
+
+pub trait FragmentShader: Sync {
+    /// Color lineal (puede superar 1.0) y alfa del fragmento
+    fn shade(&self, ctx: &ShadingContext) -> LinearRgba;
+
+    /// Superficies planas que se ven de ambos lados: no se les aplica back-face culling
+    fn is_double_sided(&self) -> bool {
+        false
+    }
+}
+
+// Atmósfera: un halo en el borde del disco, solo del lado iluminado, con la intensidad del
+// cuerpo
+fn atmosphere(ctx: &ShadingContext, color: LinearColor) -> LinearColor {
+    color * ctx.diffuse(0.0) * (ctx.rim(3.0) * ctx.params.atmosphere)
+}
+
+pub struct SunShader {
+    pub star: Star,
+}
+
+impl FragmentShader for SunShader {
+    fn shade(&self, ctx: &ShadingContext) -> LinearRgba {
+        // El sol no necesita iluminación, emite luz
+        LinearRgba::new(sun_shader(ctx, &self.star), 1.0)
+    }
+}
+
+#[derive(Default)]
+pub struct RockyPlanetShader {
+    pub params: RockyParams,
+}
+
+impl FragmentShader for RockyPlanetShader {
+    fn shade(&self, ctx: &ShadingContext) -> LinearRgba {
+        let color = rocky_planet_shader(ctx, &self.params) * ctx.diffuse(ctx.params.ambient);
+        let sky = LinearColor::from_srgb8(110, 170, 255);
+        LinearRgba::new(color + atmosphere(ctx, sky), 1.0)
+    }
+}
+
+#[derive(Default)]
+pub struct GasGiantShader {
+    pub params: GasGiantParams,
+}
+
+impl FragmentShader for GasGiantShader {
+    fn shade(&self, ctx: &ShadingContext) -> LinearRgba {
+        LinearRgba::new(gas_giant_shader(ctx, &self.params) * ctx.diffuse(ctx.params.ambient), 1.0)
+    }
+}
+
+#[derive(Default)]
+pub struct IcePlanetShader {
+    pub params: IceParams,
+}
+
+impl FragmentShader for IcePlanetShader {
+    fn shade(&self, ctx: &ShadingContext) -> LinearRgba {
+        let color = ice_planet_shader(ctx, &self.params) * ctx.diffuse(ctx.params.ambient);
+        let haze = LinearColor::from_srgb8(190, 235, 255);
+        LinearRgba::new(color + atmosphere(ctx, haze), 1.0)
+    }
+}
+
+#[derive(Default)]
+pub struct VolcanicPlanetShader {
+    pub params: VolcanicParams,
+}
+
+impl FragmentShader for VolcanicPlanetShader {
+    fn shade(&self, ctx: &ShadingContext) -> LinearRgba {
+        LinearRgba::new(volcanic_planet_shader(ctx, &self.params) * ctx.diffuse(ctx.params.ambient), 1.0)
+    }
+}
+
+#[derive(Default)]
+pub struct MoonShader {
+    pub params: MoonParams,
+}
+
+impl FragmentShader for MoonShader {
+    fn shade(&self, ctx: &ShadingContext) -> LinearRgba {
+        LinearRgba::new(moon_shader(ctx, &self.params) * ctx.diffuse(ctx.params.ambient), 1.0)
+    }
+}
+
+#[derive(Default)]
+pub struct RingShader {
+    pub params: RingParams,
+}
+
+impl FragmentShader for RingShader {
+    fn shade(&self, ctx: &ShadingContext) -> LinearRgba {
+        let ring = ring_shader(ctx, &self.params);
+        // La coordenada v va del borde interior (0) al exterior (1): los bordes se desvanecen
+        let v = ctx.tex_coords.y;
+        let edges = (v / 0.08).min((1.0 - v) / 0.08).clamp(0.0, 1.0);
+        LinearRgba::new(ring.color * ctx.diffuse(ctx.params.ambient), ring.alpha * edges)
+    }
+
+    fn is_double_sided(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Fragmento en el borde derecho de una esfera en el origen, con la cámara hacia +z y la
+    // luz a la derecha (`normal_x` = 1) o a la izquierda (-1) del fragmento
+    fn shade_with(shader: &dyn FragmentShader, params: &BodyParams, normal_x: f32) -> LinearColor {
+        let lights = [Light::new(Vec3::new(1000.0 * normal_x, 0.0, 0.0), LinearColor::white())];
+        let ctx = ShadingContext {
+            normal: Vec3::new(1.0, 0.0, 0.0),
+            tex_coords: Vec2::new(0.5, 0.5),
+            object_position: Vec3::new(1.0, 0.0, 0.0),
+            world_position: Vec3::zeros(),
+            view_direction: Vec3::new(0.0, 0.0, 1.0),
+            lights: &lights,
+            time: 0.0,
+            params,
+        };
+        shader.shade(&ctx).color
+    }
+
+    #[test]
+    fn atmosphere_comes_from_the_body_params() {
+        let shader = RockyPlanetShader::default();
+        let clear = shade_with(&shader, &BodyParams::new(0.2, 0.0), 1.0);
+        let hazy = shade_with(&shader, &BodyParams::new(0.2, 0.6), 1.0);
+        assert_ne!(clear, hazy);
+        // El halo es azul: suma sobre todo en el canal azul
+        assert!(hazy.b - clear.b > hazy.r - clear.r);
+    }
+
+    #[test]
+    fn ambient_comes_from_the_body_params() {
+        // Lado nocturno: solo llega la luz ambiental
+        let shader = MoonShader::default();
+        let dim = shade_with(&shader, &BodyParams::new(0.1, 0.0), -1.0);
+        let bright = shade_with(&shader, &BodyParams::new(0.4, 0.0), -1.0);
+        assert!(bright.luminance() > dim.luminance() * 3.9);
+    }
+}
@@ -1,7 +1,21 @@
 use nalgebra_glm::{Vec3, Vec4, Mat3};
 use crate::vertex::Vertex;
+use crate::triangle::Interpolation;
 use crate::Uniforms;
 
+// Etapa de vértices programable: lleva cada vértice a clip space (`clip_position`) y
+// transforma sus atributos. La división de perspectiva y el viewport vienen después, fijos.
+// Cualquier función `Fn(&Vertex, &Uniforms) -> Vertex` sirve, empezando por `vertex_shader`
+pub trait VertexShader {
+  fn shade(&self, vertex: &Vertex, uniforms: &Uniforms) -> Vertex;
+}
+
+impl<F: Fn(&Vertex, &Uniforms) -> Vertex> VertexShader for F {
+  fn shade(&self, vertex: &Vertex, uniforms: &Uniforms) -> Vertex {
+    self(vertex, uniforms)
+  }
+}
+
 pub fn vertex_shader(vertex: &Vertex, uniforms: &Uniforms) -> Vertex {
   // Transform position
   let position = Vec4::new(
@@ -10,15 +24,9 @@ pub fn vertex_shader(vertex: &Vertex, uniforms: &Uniforms) -> Vertex {
     vertex.position.z,
     1.0
   );
-  let transformed = uniforms.model_matrix * position;
-
-  // Perform perspective division
-  let w = transformed.w;
-  let transformed_position = Vec3::new(
-    transformed.x / w,
-    transformed.y / w,
-    transformed.z / w
-  );
+  let world_position = uniforms.model_matrix * position;
+  // A clip space; la división de perspectiva se hace después del recorte (`perspective_divide`)
+  let clip_position = uniforms.projection_matrix * world_position;
 
   // Transform normal
 
@@ -27,7 +35,9 @@ pub fn vertex_shader(vertex: &Vertex, uniforms: &Uniforms) -> Vertex {
     uniforms.model_matrix[4], uniforms.model_matrix[5], uniforms.model_matrix[6],
     uniforms.model_matrix[8], uniforms.model_matrix[9], uniforms.model_matrix[10]
   );
-  let normal_matrix = model_mat3.transpose().try_inverse().unwrap_or(Mat3::identity());
+  // Inversa transpuesta de la parte 3x3 del modelo. Los índices de Mat4 van por columnas y
+  // `Mat3::new` por filas, así que `model_mat3` ya es la transpuesta
+  let normal_matrix = model_mat3.try_inverse().unwrap_or(Mat3::identity());
 
   let transformed_normal = normal_matrix * vertex.normal;
 
@@ -37,7 +47,23 @@ pub fn vertex_shader(vertex: &Vertex, uniforms: &Uniforms) -> Vertex {
     normal: vertex.normal,
     tex_coords: vertex.tex_coords,
     color: vertex.color,
-    transformed_position,
+    clip_position,
+    inv_w: 1.0,
+    world_position: world_position.xyz(),
+    transformed_position: vertex.position,
     transformed_normal,
   }
 }
+
+// División de perspectiva y viewport: de clip space a pixeles (x, y) y profundidad (z)
+pub fn perspective_divide(vertex: &mut Vertex, uniforms: &Uniforms) {
+  let clip = vertex.clip_position;
+  let ndc = Vec4::new(clip.x / clip.w, clip.y / clip.w, clip.z / clip.w, 1.0);
+  let screen = uniforms.viewport_matrix * ndc;
+  vertex.transformed_position = Vec3::new(screen.x, screen.y, screen.z);
+  // En modo afín se ignora w: los atributos se interpolan linealmente en pantalla
+  vertex.inv_w = match uniforms.interpolation {
+    Interpolation::Perspective => 1.0 / clip.w,
+    Interpolation::Affine => 1.0,
+  };
+}
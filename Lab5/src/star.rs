@@ -0,0 +1,128 @@
+// star.rs
+// Estrella central del sistema: su temperatura define el color (cuerpo negro) del disco
+// y de la luz que reciben los planetas; la luminosidad escala ambos
+
+use crate::color::LinearColor;
+
+// Temperatura superficial del Sol, en kelvin. Su luz se toma como blanca: las demás
+// estrellas tiñen la escena relativo a ella
+pub const SUN_TEMPERATURE: f32 = 5778.0;
+
+// Nombres aceptados por `Star::from_name`, en el orden en que se recorren con la tecla K
+pub const PRESETS: [&str; 4] = ["sun", "red-dwarf", "white-dwarf", "blue-giant"];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Star {
+    pub name: &'static str,
+    // Temperatura superficial en kelvin
+    pub temperature: f32,
+    // Brillo relativo al Sol (1.0)
+    pub luminosity: f32,
+    // Tamaño en pantalla (el Sol mide 140)
+    pub scale: f32,
+    // Colores derivados de la temperatura, calculados una vez
+    core: LinearColor,
+    mid: LinearColor,
+    limb: LinearColor,
+    light: LinearColor,
+}
+
+impl Star {
+    pub fn new(name: &'static str, temperature: f32, luminosity: f32, scale: f32) -> Self {
+        // El centro es el cuerpo negro a la temperatura de la estrella. Oscurecimiento hacia
+        // el borde: el limbo se ve un poco más frío (capas más altas) y menos brillante
+        let core = LinearColor::from_temperature(temperature);
+        let mid = LinearColor::from_temperature(temperature * 0.93) * 0.8;
+        let limb = LinearColor::from_temperature(temperature * 0.85) * 0.4;
+
+        // Balance de blancos respecto al Sol: con SUN_TEMPERATURE la luz es blanca exacta
+        let tint = LinearColor::from_temperature(temperature);
+        let reference = LinearColor::from_temperature(SUN_TEMPERATURE);
+        let light = LinearColor::new(tint.r / reference.r, tint.g / reference.g, tint.b / reference.b) * luminosity;
+
+        Star { name, temperature, luminosity, scale, core, mid, limb, light }
+    }
+
+    pub fn sun() -> Self {
+        Star::new("sun", SUN_TEMPERATURE, 1.0, 140.0)
+    }
+
+    pub fn red_dwarf() -> Self {
+        Star::new("red-dwarf", 3200.0, 1.0, 90.0)
+    }
+
+    pub fn white_dwarf() -> Self {
+        Star::new("white-dwarf", 12000.0, 0.8, 45.0)
+    }
+
+    pub fn blue_giant() -> Self {
+        Star::new("blue-giant", 22000.0, 0.9, 180.0)
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "sun" => Some(Star::sun()),
+            "red-dwarf" => Some(Star::red_dwarf()),
+            "white-dwarf" => Some(Star::white_dwarf()),
+            "blue-giant" => Some(Star::blue_giant()),
+            _ => None,
+        }
+    }
+
+    /// Siguiente tipo de estrella en `PRESETS` (vuelve al Sol después del último)
+    pub fn next(&self) -> Self {
+        let index = PRESETS.iter().position(|&name| name == self.name).map_or(0, |i| i + 1);
+        Star::from_name(PRESETS[index % PRESETS.len()]).unwrap()
+    }
+
+    /// La misma estrella con otra temperatura. Si se aleja de la del preajuste ya no es ese
+    /// tipo de estrella (una "sun" a 3000 K es una enana roja), así que pasa a llamarse "custom"
+    pub fn with_temperature(self, temperature: f32) -> Self {
+        let preset_temperature = Star::from_name(self.name).map(|preset| preset.temperature);
+        let name = if preset_temperature == Some(temperature) { self.name } else { "custom" };
+        Star::new(name, temperature, self.luminosity, self.scale)
+    }
+
+    /// La misma estrella con otra luminosidad
+    pub fn with_luminosity(self, luminosity: f32) -> Self {
+        Star::new(self.name, self.temperature, luminosity, self.scale)
+    }
+
+    /// Colores del disco del centro al borde, sin escalar por la luminosidad
+    pub fn disc_colors(&self) -> (LinearColor, LinearColor, LinearColor) {
+        (self.core, self.mid, self.limb)
+    }
+
+    /// Color e intensidad de la luz que ilumina a los planetas
+    pub fn light(&self) -> LinearColor {
+        self.light
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn changing_the_temperature_marks_the_star_custom() {
+        let star = Star::sun().with_temperature(3000.0);
+        assert_eq!(star.name, "custom");
+        assert_eq!(star.temperature, 3000.0);
+        // El resto de la estrella no cambia
+        assert_eq!(star.scale, Star::sun().scale);
+        assert_eq!(star.luminosity, Star::sun().luminosity);
+    }
+
+    #[test]
+    fn the_preset_temperature_keeps_the_name() {
+        for name in PRESETS {
+            let star = Star::from_name(name).unwrap();
+            assert_eq!(star.with_temperature(star.temperature), star);
+        }
+    }
+
+    #[test]
+    fn a_custom_star_cycles_back_to_the_sun() {
+        assert_eq!(Star::red_dwarf().with_temperature(5000.0).next(), Star::sun());
+    }
+}
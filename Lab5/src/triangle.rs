@@ -1,93 +1,164 @@
-use nalgebra_glm::{Vec3, dot};
-use crate::fragment::Fragment;
+use nalgebra_glm::Vec3;
+use crate::fragment::{Fragment, Varyings};
+use crate::framebuffer::RasterTarget;
 use crate::vertex::Vertex;
-use crate::shader;
-
-// Tipo de shader activo
-#[derive(Clone, Copy, PartialEq)]
-pub enum ShaderType {
-    Sun,
-    RockyPlanet,
-    GasGiant,
-    IcePlanet,
-    VolcanicPlanet,
-    Moon,
+use crate::shader::{FragmentShader, ShadingContext};
+use crate::Uniforms;
+
+// Cómo se interpolan los atributos de los vértices dentro del triángulo
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Interpolation {
+    // Ponderando por 1/w: correcto con proyección en perspectiva
+    Perspective,
+    // Lineal en pantalla (como las consolas sin corrección): se deforma con la perspectiva
+    Affine,
 }
 
-pub fn triangle(v1: &Vertex, v2: &Vertex, v3: &Vertex, shader_type: ShaderType, time: f32) -> Vec<Fragment> {
-    let mut fragments = Vec::new();
-    let (a, b, c) = (v1.transformed_position, v2.transformed_position, v3.transformed_position);
+impl Interpolation {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Interpolation::Perspective => "perspectiva",
+            Interpolation::Affine => "afín",
+        }
+    }
+
+    pub fn toggle(self) -> Self {
+        match self {
+            Interpolation::Perspective => Interpolation::Affine,
+            Interpolation::Affine => Interpolation::Perspective,
+        }
+    }
+}
+
+// Bits de subpixel del punto fijo: los vértices se ajustan a 1/256 de pixel
+const SUBPIXEL_BITS: u32 = 8;
+const SUBPIXEL_ONE: i64 = 1 << SUBPIXEL_BITS;
+
+// Función de arista en punto fijo, E(p) = (p.x - from.x) * dy - (p.y - from.y) * dx, evaluada
+// de forma incremental: avanzar un pixel en x suma `step_x` y bajar una fila suma `step_y`.
+// Con enteros el resultado es exacto, así que no depende de dónde empiece el recorrido
+struct Edge {
+    row: i64,
+    step_x: i64,
+    step_y: i64,
+    // Regla top-left: un pixel justo sobre la arista (E = 0) solo se cubre si es una arista
+    // superior o izquierda, así dos triángulos vecinos no pintan dos veces su arista común
+    bias: i64,
+}
+
+impl Edge {
+    // Arista de `from` a `to` (en punto fijo), evaluada en el centro del pixel (x, y)
+    fn new(from: (i64, i64), to: (i64, i64), x: i32, y: i32) -> Self {
+        let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+        let px = ((x as i64) << SUBPIXEL_BITS) + SUBPIXEL_ONE / 2;
+        let py = ((y as i64) << SUBPIXEL_BITS) + SUBPIXEL_ONE / 2;
+        // Con y hacia abajo y área positiva, el interior queda a la derecha de una arista
+        // izquierda (dy > 0) y debajo de una superior (horizontal con dx < 0)
+        let top_left = dy > 0 || (dy == 0 && dx < 0);
+        Edge {
+            row: (px - from.0) * dy - (py - from.1) * dx,
+            step_x: dy * SUBPIXEL_ONE,
+            step_y: -dx * SUBPIXEL_ONE,
+            bias: if top_left { 0 } else { -1 },
+        }
+    }
+}
+
+// Posición en pantalla ajustada a la grilla de subpixeles
+fn snap(vertex: &Vertex) -> (i64, i64) {
+    let position = vertex.transformed_position;
+    let one = SUBPIXEL_ONE as f32;
+    ((position.x * one).round() as i64, (position.y * one).round() as i64)
+}
+
+// Rasteriza el triángulo solo dentro de `target` (la pantalla o un tile) y le entrega cada
+// fragmento en cuanto se sombrea, sin listas intermedias. Solo interpola: el color lo calcula
+// `shader` con los `uniforms` del cuerpo. La profundidad se prueba antes de sombrear: los
+// fragmentos tapados no corren el shader
+pub fn triangle(v1: &Vertex, v2: &Vertex, v3: &Vertex, shader: &dyn FragmentShader, uniforms: &Uniforms, target: &mut impl RasterTarget) {
+    let clip = target.rect();
     
-    let (min_x, min_y, max_x, max_y) = calculate_bounding_box(&a, &b, &c);
+    // Las superficies de doble cara pueden llegar con el giro invertido: se ordenan los
+    // vértices para que el área sea positiva
+    let (s1, s2, s3) = (snap(v1), snap(v2), snap(v3));
+    let signed_area = (s3.0 - s1.0) * (s2.1 - s1.1) - (s3.1 - s1.1) * (s2.0 - s1.0);
+    let (v2, v3, s2, s3) = if signed_area < 0 { (v3, v2, s3, s2) } else { (v2, v3, s2, s3) };
+    let triangle_area = signed_area.abs();
+    if triangle_area == 0 {
+        return;
+    }
+    let (a, b, c) = (v1.transformed_position, v2.transformed_position, v3.transformed_position);
     
-    let light_dir = Vec3::new(0.0, 0.0, -1.0);
+    // Pixeles cuyo centro cae dentro de la caja del triángulo, recortados a `clip`
+    let half = SUBPIXEL_ONE / 2;
+    let first_center = |min: i64| (min - half + SUBPIXEL_ONE - 1).div_euclid(SUBPIXEL_ONE);
+    let last_center = |max: i64| (max - half).div_euclid(SUBPIXEL_ONE);
+    let min_x = first_center(s1.0.min(s2.0).min(s3.0)).max(clip.x as i64) as i32;
+    let min_y = first_center(s1.1.min(s2.1).min(s3.1)).max(clip.y as i64) as i32;
+    let max_x = last_center(s1.0.max(s2.0).max(s3.0)).min((clip.x + clip.width) as i64 - 1) as i32;
+    let max_y = last_center(s1.1.max(s2.1).max(s3.1)).min((clip.y + clip.height) as i64 - 1) as i32;
     
-    let triangle_area = edge_function(&a, &b, &c);
+    // Cada arista da el peso del vértice opuesto
+    let mut e1 = Edge::new(s2, s3, min_x, min_y);
+    let mut e2 = Edge::new(s3, s1, min_x, min_y);
+    let mut e3 = Edge::new(s1, s2, min_x, min_y);
+    let inv_area = 1.0 / triangle_area as f32;
     
     // Iterate over each pixel in the bounding box
     for y in min_y..=max_y {
+        let (mut d1, mut d2, mut d3) = (e1.row, e2.row, e3.row);
         for x in min_x..=max_x {
-            let point = Vec3::new(x as f32 + 0.5, y as f32 + 0.5, 0.0);
-            
-            // Calculate barycentric coordinates
-            let (w1, w2, w3) = barycentric_coordinates(&point, &a, &b, &c, triangle_area);
-            
             // Check if the point is inside the triangle
-            if w1 >= 0.0 && w1 <= 1.0 &&
-               w2 >= 0.0 && w2 <= 1.0 &&
-               w3 >= 0.0 && w3 <= 1.0 {
-                
-                // Interpolate normal
-                let normal = v1.transformed_normal * w1 + v2.transformed_normal * w2 + v3.transformed_normal * w3;
-                let normal = normal.normalize();
-                
-                // Interpolate world position (sin transformación de pantalla)
-                let world_pos = v1.position * w1 + v2.position * w2 + v3.position * w3;
-                
-                // Calculate lighting intensity
-                let intensity = dot(&normal, &light_dir).max(0.0);
-                
-                // Aplica el shader correspondiente
-                let base_color = match shader_type {
-                    ShaderType::Sun => {
-                        // El sol no necesita iluminación, emite luz
-                        shader::sun_shader(&world_pos, time)
-                    },
-                    ShaderType::RockyPlanet => {
-                        let color = shader::rocky_planet_shader(&world_pos, time);
-                        color * intensity.max(0.2) // Luz ambiental mínima
-                    },
-                    ShaderType::GasGiant => {
-                        let color = shader::gas_giant_shader(&world_pos, time);
-                        color * intensity.max(0.2)
-                    },
-                    ShaderType::IcePlanet => {
-                        let color = shader::ice_planet_shader(&world_pos, time);
-                        color * intensity.max(0.3) // Los planetas helados reflejan más luz
-                    },
-                    ShaderType::VolcanicPlanet => {
-                        let color = shader::volcanic_planet_shader(&world_pos, time);
-                        // Los volcanes emiten su propia luz
-                        color * intensity.max(0.4)
-                    },
-                    ShaderType::Moon => {
-                        let color = shader::moon_shader(&world_pos);
-                        color * intensity.max(0.15)
-                    },
-                };
+            if d1 + e1.bias >= 0 && d2 + e2.bias >= 0 && d3 + e3.bias >= 0 {
+                // Calculate barycentric coordinates
+                let (w1, w2, w3) = (d1 as f32 * inv_area, d2 as f32 * inv_area, d3 as f32 * inv_area);
                 
-                // Interpolate depth
+                // Interpolate depth: z ya pasó por la división de perspectiva, así que es
+                // lineal en pantalla y no lleva corrección
                 let depth = a.z * w1 + b.z * w2 + c.z * w3;
                 
-                fragments.push(Fragment::new(x as f32, y as f32, base_color, depth));
+                // Prueba de profundidad temprana: solo los fragmentos visibles corren el shader
+                if target.depth_test(x as usize, y as usize, depth) {
+                    // Corrección de perspectiva: los atributos divididos por w sí varían linealmente
+                    // en pantalla, así que se ponderan con 1/w y se vuelve a normalizar
+                    let (p1, p2, p3) = (w1 * v1.inv_w, w2 * v2.inv_w, w3 * v3.inv_w);
+                    let sum = p1 + p2 + p3;
+                    let (p1, p2, p3) = (p1 / sum, p2 / sum, p3 / sum);
+                    
+                    // Interpolate normal
+                    let normal = v1.transformed_normal * p1 + v2.transformed_normal * p2 + v3.transformed_normal * p3;
+                    let normal = normal.normalize();
+                    
+                    // Interpolate object and world position (sin transformación de pantalla)
+                    let varyings = Varyings {
+                        object_position: v1.position * p1 + v2.position * p2 + v3.position * p3,
+                        world_position: v1.world_position * p1 + v2.world_position * p2 + v3.world_position * p3,
+                        normal,
+                        tex_coords: v1.tex_coords * p1 + v2.tex_coords * p2 + v3.tex_coords * p3,
+                    };
+                    
+                    // El shader decide el color, la iluminación y la transparencia
+                    let shaded = shader.shade(&ShadingContext::new(&varyings, uniforms));
+                    
+                    target.write(
+                        Fragment::new(x as f32, y as f32, shaded.color, depth)
+                            .with_alpha(shaded.alpha)
+                            .with_normal(normal)
+                            .with_tex_coords(varyings.tex_coords),
+                    );
+                }
             }
+            d1 += e1.step_x;
+            d2 += e2.step_x;
+            d3 += e3.step_x;
         }
+        e1.row += e1.step_y;
+        e2.row += e2.step_y;
+        e3.row += e3.step_y;
     }
-    
-    fragments
 }
 
-fn calculate_bounding_box(v1: &Vec3, v2: &Vec3, v3: &Vec3) -> (i32, i32, i32, i32) {
+pub fn calculate_bounding_box(v1: &Vec3, v2: &Vec3, v3: &Vec3) -> (i32, i32, i32, i32) {
     let min_x = v1.x.min(v2.x).min(v3.x).floor() as i32;
     let min_y = v1.y.min(v2.y).min(v3.y).floor() as i32;
     let max_x = v1.x.max(v2.x).max(v3.x).ceil() as i32;
@@ -96,14 +167,96 @@ fn calculate_bounding_box(v1: &Vec3, v2: &Vec3, v3: &Vec3) -> (i32, i32, i32, i3
     (min_x, min_y, max_x, max_y)
 }
 
-fn barycentric_coordinates(p: &Vec3, a: &Vec3, b: &Vec3, c: &Vec3, area: f32) -> (f32, f32, f32) {
-    let w1 = edge_function(b, c, p) / area;
-    let w2 = edge_function(c, a, p) / area;
-    let w3 = edge_function(a, b, p) / area;
-    
-    (w1, w2, w3)
+// Un triángulo en pantalla da la espalda a la cámara si sus vértices giran en sentido
+// contrario a las caras visibles de los modelos; los degenerados (sin área) también se descartan
+pub fn is_back_facing(a: &Vec3, b: &Vec3, c: &Vec3) -> bool {
+    edge_function(a, b, c) <= 0.0
 }
 
 fn edge_function(a: &Vec3, b: &Vec3, c: &Vec3) -> f32 {
     (c.x - a.x) * (b.y - a.y) - (c.y - a.y) * (b.x - a.x)
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra_glm::Vec2;
+
+    use super::*;
+    use crate::color::{LinearColor, LinearRgba};
+    use crate::framebuffer::Framebuffer;
+    use crate::shader::BodyParams;
+
+    struct WhiteShader;
+
+    impl FragmentShader for WhiteShader {
+        fn shade(&self, _ctx: &ShadingContext) -> LinearRgba {
+            LinearRgba::new(LinearColor::white(), 1.0)
+        }
+    }
+
+    // Cuadrado de 2.25 a 29.75: ningún centro de pixel cae en su borde, así los pixeles
+    // cubiertos son exactamente los de 2 a 29 en cada eje
+    const MIN: f32 = 2.25;
+    const MAX: f32 = 29.75;
+
+    // Overdraw de cada pixel tras dibujar los triángulos en un framebuffer de 32x32
+    fn overdraw(triangles: &[[(f32, f32); 3]]) -> Vec<u32> {
+        let params = BodyParams::default();
+        let uniforms = Uniforms::for_tests(&params);
+        let mut framebuffer = Framebuffer::new(32, 32);
+        for corners in triangles {
+            let [a, b, c] = corners.map(|(x, y)| Vertex::new(Vec3::new(x, y, 0.5), Vec3::new(0.0, 0.0, -1.0), Vec2::zeros()));
+            triangle(&a, &b, &c, &WhiteShader, &uniforms, &mut framebuffer);
+        }
+        framebuffer.overdraw
+    }
+
+    fn assert_square_covered_once(overdraw: &[u32]) {
+        for y in 0..32 {
+            for x in 0..32 {
+                let inside = (2..=29).contains(&x) && (2..=29).contains(&y);
+                let expected = if inside { 1 } else { 0 };
+                assert_eq!(overdraw[y * 32 + x], expected, "pixel ({x}, {y})");
+            }
+        }
+    }
+
+    #[test]
+    fn shared_diagonal_is_drawn_once() {
+        // La diagonal pasa por los centros de los pixeles (k + 0.5, k + 0.5)
+        let triangles = [[(MIN, MIN), (MAX, MIN), (MAX, MAX)], [(MIN, MIN), (MAX, MAX), (MIN, MAX)]];
+        assert_square_covered_once(&overdraw(&triangles));
+    }
+
+    #[test]
+    fn fan_around_a_vertex_is_watertight() {
+        // Abanico desde el centro hacia puntos del borde; las aristas hacia las esquinas pasan
+        // por centros de pixeles, y se mezclan ambos sentidos de giro
+        let center = (16.0, 16.0);
+        let rim = [
+            (MIN, MIN), (9.5, MIN), (MAX, MIN), (MAX, 13.0), (MAX, MAX),
+            (21.0, MAX), (MIN, MAX), (MIN, 7.7),
+        ];
+        let triangles: Vec<_> = (0..rim.len())
+            .map(|i| {
+                let (a, b) = (rim[i], rim[(i + 1) % rim.len()]);
+                if i % 2 == 0 { [center, a, b] } else { [center, b, a] }
+            })
+            .collect();
+        assert_square_covered_once(&overdraw(&triangles));
+    }
+
+    #[test]
+    fn back_faces_are_culled_by_winding() {
+        // Con y hacia abajo, (0,0) -> (0,10) -> (10,0) gira en sentido antihorario en pantalla:
+        // es una cara visible; el mismo triángulo al revés da la espalda
+        let (a, b, c) = (Vec3::new(0.0, 0.0, 0.5), Vec3::new(0.0, 10.0, 0.5), Vec3::new(10.0, 0.0, 0.5));
+        assert!(!is_back_facing(&a, &b, &c));
+        assert!(is_back_facing(&a, &c, &b));
+        // Rotar los vértices no cambia el giro
+        assert!(!is_back_facing(&b, &c, &a));
+        assert!(is_back_facing(&c, &b, &a));
+        // Sin área: se descarta
+        assert!(is_back_facing(&a, &b, &Vec3::new(0.0, 20.0, 0.5)));
+    }
+}
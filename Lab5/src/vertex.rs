@@ -1,5 +1,5 @@
-use nalgebra_glm::{Vec2, Vec3};
-use crate::color::Color;
+use nalgebra_glm::{Vec2, Vec3, Vec4};
+use crate::color::{Color, LinearColor};
 
 #[derive(Clone, Debug)]
 pub struct Vertex {
@@ -7,6 +7,13 @@ pub struct Vertex {
   pub normal: Vec3,
   pub tex_coords: Vec2,
   pub color: Color,
+  // Posición en clip space (coordenadas homogéneas), antes de la división de perspectiva
+  pub clip_position: Vec4,
+  // 1/w tras la división de perspectiva, para interpolar con corrección de perspectiva
+  // (1.0 en modo afín)
+  pub inv_w: f32,
+  // Posición en el espacio de mundo (modelo ya escalado, rotado y trasladado)
+  pub world_position: Vec3,
   pub transformed_position: Vec3,
   pub transformed_normal: Vec3,
 }
@@ -18,6 +25,9 @@ impl Vertex {
       normal,
       tex_coords,
       color: Color::black(),
+      clip_position: Vec4::new(position.x, position.y, position.z, 1.0),
+      inv_w: 1.0,
+      world_position: position,
       transformed_position: position,
       transformed_normal: normal,
     }
@@ -29,6 +39,9 @@ impl Vertex {
       normal: Vec3::new(0.0, 0.0, 0.0),
       tex_coords: Vec2::new(0.0, 0.0),
       color,
+      clip_position: Vec4::new(position.x, position.y, position.z, 1.0),
+      inv_w: 1.0,
+      world_position: Vec3::new(0.0, 0.0, 0.0),
       transformed_position: Vec3::new(0.0, 0.0, 0.0),
       transformed_normal: Vec3::new(0.0, 0.0, 0.0),
     }
@@ -38,6 +51,21 @@ impl Vertex {
     self.transformed_position = position;
     self.transformed_normal = normal;
   }
+
+  // Interpola todos los atributos hacia `other` (t = 0 devuelve `self`); lo usa el recorte
+  pub fn lerp(&self, other: &Vertex, t: f32) -> Vertex {
+    Vertex {
+      position: self.position.lerp(&other.position, t),
+      normal: self.normal.lerp(&other.normal, t),
+      tex_coords: self.tex_coords.lerp(&other.tex_coords, t),
+      color: LinearColor::from(self.color).lerp(LinearColor::from(other.color), t).to_srgb(),
+      clip_position: self.clip_position.lerp(&other.clip_position, t),
+      inv_w: self.inv_w + (other.inv_w - self.inv_w) * t,
+      world_position: self.world_position.lerp(&other.world_position, t),
+      transformed_position: self.transformed_position.lerp(&other.transformed_position, t),
+      transformed_normal: self.transformed_normal.lerp(&other.transformed_normal, t),
+    }
+  }
 }
 
 impl Default for Vertex {
@@ -47,6 +75,9 @@ impl Default for Vertex {
       normal: Vec3::new(0.0, 1.0, 0.0),
       tex_coords: Vec2::new(0.0, 0.0),
       color: Color::black(),
+      clip_position: Vec4::new(0.0, 0.0, 0.0, 1.0),
+      inv_w: 1.0,
+      world_position: Vec3::new(0.0, 0.0, 0.0),
       transformed_position: Vec3::new(0.0, 0.0, 0.0),
       transformed_normal: Vec3::new(0.0, 1.0, 0.0),
     }
@@ -0,0 +1,97 @@
+// y4m.rs
+// Exportación de video sin comprimir en formato YUV4MPEG2 (Y4M), 4:2:0
+
+use std::io::{self, Write};
+
+pub struct Y4mWriter<W: Write> {
+    writer: W,
+    width: usize,
+    height: usize,
+    y_plane: Vec<u8>,
+    u_plane: Vec<u8>,
+    v_plane: Vec<u8>,
+}
+
+impl<W: Write> Y4mWriter<W> {
+    /// Escribe la cabecera del stream. La tasa de frames es `fps_num / fps_den`.
+    pub fn new(mut writer: W, width: usize, height: usize, fps_num: u32, fps_den: u32) -> io::Result<Self> {
+        let divisor = gcd(fps_num, fps_den).max(1);
+        writeln!(
+            writer,
+            "YUV4MPEG2 W{} H{} F{}:{} Ip A1:1 C420jpeg",
+            width,
+            height,
+            fps_num / divisor,
+            fps_den / divisor
+        )?;
+
+        let chroma_len = width.div_ceil(2) * height.div_ceil(2);
+        Ok(Y4mWriter {
+            writer,
+            width,
+            height,
+            y_plane: vec![0; width * height],
+            u_plane: vec![0; chroma_len],
+            v_plane: vec![0; chroma_len],
+        })
+    }
+
+    /// Convierte un frame RGB (0x00RRGGBB) a YUV BT.601 y lo escribe
+    pub fn write_frame(&mut self, pixels: &[u32]) -> io::Result<()> {
+        if pixels.len() != self.width * self.height {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "el tamaño del frame no coincide con el del video",
+            ));
+        }
+
+        for (luma, &pixel) in self.y_plane.iter_mut().zip(pixels) {
+            let (r, g, b) = channels(pixel);
+            *luma = (((66 * r + 129 * g + 25 * b + 128) >> 8) + 16) as u8;
+        }
+
+        // Croma promediando bloques de 2x2 pixeles
+        let chroma_width = self.width.div_ceil(2);
+        for cy in 0..self.height.div_ceil(2) {
+            for cx in 0..chroma_width {
+                let (mut r, mut g, mut b, mut count) = (0, 0, 0, 0);
+                for y in (cy * 2)..(cy * 2 + 2).min(self.height) {
+                    for x in (cx * 2)..(cx * 2 + 2).min(self.width) {
+                        let (pr, pg, pb) = channels(pixels[y * self.width + x]);
+                        r += pr;
+                        g += pg;
+                        b += pb;
+                        count += 1;
+                    }
+                }
+                let (r, g, b) = (r / count, g / count, b / count);
+
+                let index = cy * chroma_width + cx;
+                self.u_plane[index] = (((-38 * r - 74 * g + 112 * b + 128) >> 8) + 128) as u8;
+                self.v_plane[index] = (((112 * r - 94 * g - 18 * b + 128) >> 8) + 128) as u8;
+            }
+        }
+
+        self.writer.write_all(b"FRAME\n")?;
+        self.writer.write_all(&self.y_plane)?;
+        self.writer.write_all(&self.u_plane)?;
+        self.writer.write_all(&self.v_plane)
+    }
+
+    pub fn finish(mut self) -> io::Result<W> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+fn channels(pixel: u32) -> (i32, i32, i32) {
+    (
+        ((pixel >> 16) & 0xFF) as i32,
+        ((pixel >> 8) & 0xFF) as i32,
+        (pixel & 0xFF) as i32,
+    )
+}
+
+fn gcd(a: u32, b: u32) -> u32 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}